@@ -0,0 +1,125 @@
+//! The aggregation of commits and working time per calendar day.
+//!
+//! The daily activity is the base of all time based aggregations, see the
+//! module `period` for longer periods.
+
+use std::collections::BTreeMap;
+
+/// The activity during a certain period of time.
+#[derive(Clone, Debug)]
+pub struct Activity {
+    /// The time spent working per author.
+    ///
+    /// Authors who committed without working time are listed with zero.
+    authors: BTreeMap<String, chrono::Duration>,
+
+    /// The number of changed binary files.
+    binary_files: usize,
+
+    /// The number of commits.
+    commits: usize,
+
+    /// The inserted and deleted lines.
+    loc: crate::loc::LocStats,
+
+    /// The time spent working.
+    work: chrono::Duration,
+}
+
+impl Default for Activity {
+    fn default() -> Self {
+        Self {
+            authors: BTreeMap::new(),
+            binary_files: 0,
+            commits: 0,
+            loc: crate::loc::LocStats::default(),
+            work: chrono::Duration::zero(),
+        }
+    }
+}
+
+impl Activity {
+    /// Sums up the commits and the working time per calendar day.
+    ///
+    /// The days are determined in the given time zone. Working time which
+    /// spans several days is split at midnight.
+    pub fn per_day(
+        sessions: &[crate::session::Session],
+        filter: &crate::Filter,
+        timezone: crate::timezone::Timezone,
+    ) -> BTreeMap<chrono::NaiveDate, Self> {
+        let mut days: BTreeMap<_, Self> = BTreeMap::new();
+
+        for session in sessions {
+            for commit in session.commits() {
+                let date = timezone.convert(commit.date());
+                let day = days.entry(date.naive_local().date()).or_default();
+
+                day.authors
+                    .entry(commit.author().name().to_owned())
+                    .or_insert_with(chrono::Duration::zero);
+                day.binary_files += commit.binary_files(filter);
+                day.commits += 1;
+                day.loc += commit.loc_stats(filter);
+            }
+
+            for interval in session.intervals() {
+                let author = interval.commit().author().name();
+                for (date, work) in timezone.split_days(interval.start(), interval.end()) {
+                    let day = days.entry(date).or_default();
+                    let by_author = day
+                        .authors
+                        .entry(author.to_owned())
+                        .or_insert_with(chrono::Duration::zero);
+
+                    *by_author = *by_author + work;
+                    day.work = day.work + work;
+                }
+            }
+        }
+
+        days
+    }
+
+    /// The getter method for the field `authors` of the corresponding struct.
+    pub fn authors(&self) -> &BTreeMap<String, chrono::Duration> {
+        &self.authors
+    }
+
+    /// The getter method for the field `binary_files` of the corresponding
+    /// struct.
+    pub fn binary_files(&self) -> usize {
+        self.binary_files
+    }
+
+    /// The getter method for the field `commits` of the corresponding struct.
+    pub fn commits(&self) -> usize {
+        self.commits
+    }
+
+    /// The getter method for the field `loc` of the corresponding struct.
+    pub fn loc(&self) -> crate::loc::LocStats {
+        self.loc
+    }
+
+    /// Adds the activity of another period to this one.
+    pub fn merge(&mut self, other: &Self) {
+        for (author, work) in &other.authors {
+            let by_author = self
+                .authors
+                .entry(author.clone())
+                .or_insert_with(chrono::Duration::zero);
+
+            *by_author = *by_author + *work;
+        }
+        self.binary_files += other.binary_files;
+        self.commits += other.commits;
+        self.loc += other.loc;
+        self.work = self.work + other.work;
+    }
+
+    /// The getter method for the field `work` of the corresponding struct.
+    pub fn work(&self) -> chrono::Duration {
+        self.work
+    }
+}
@@ -0,0 +1,312 @@
+//! The attribution of the working time to the changed files.
+//!
+//! The estimate tells how long the work took, the attribution tells what it
+//! was spent on. The duration of every session is distributed over the files
+//! changed by its commits, which are then aggregated per file, directory or
+//! language.
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{
+    group::Record,
+    hotspots::Renames,
+    session::{hours, Session},
+    table::{Alignment, Table},
+    timezone::Timezone,
+    Commit,
+};
+
+/// The ways to distribute the working time over the changed files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Attribution {
+    /// The time since the previous commit of the session goes to a commit,
+    /// split evenly between its files.
+    Interval,
+
+    /// The duration of a session is split evenly between its commits
+    /// changing any files, then evenly between their files.
+    Even,
+
+    /// The duration of a session is split between the files of its commits
+    /// by their inserted and deleted lines.
+    Churn,
+}
+
+impl Attribution {
+    /// Distributes the duration of the given session over its commits and
+    /// their files matching the given filter.
+    ///
+    /// Every commit is mapped to its hours and the hours of its files, in the
+    /// order of `Commit::locs`. Sessions without any matching files attribute
+    /// their time to the commits with the `Interval` strategy. With `Churn`,
+    /// sessions without any inserted or deleted lines, e.g. because they only
+    /// changed binary files, fall back to `Even`.
+    pub fn distribute<'a>(
+        self,
+        session: &Session<'a>,
+        filter: &crate::Filter,
+    ) -> HashMap<&'a str, (f64, Vec<f64>)> {
+        let files: Vec<(&Commit, Vec<&crate::LocDiff>)> = session
+            .commits()
+            .iter()
+            .map(|commit| {
+                let files = commit
+                    .locs()
+                    .iter()
+                    .filter(|loc| filter.check_loc(loc))
+                    .collect();
+
+                (*commit, files)
+            })
+            .collect();
+        let duration = hours(session.duration());
+        let changing = files.iter().filter(|(_, files)| !files.is_empty()).count();
+        let churn: u64 = files
+            .iter()
+            .flat_map(|(_, files)| files.iter().map(|file| file.loc_stats().churn()))
+            .sum();
+        let strategy = match self {
+            _ if changing == 0 => Self::Interval,
+            Self::Churn if churn == 0 => Self::Even,
+            strategy => strategy,
+        };
+        let intervals: HashMap<&str, f64> = session
+            .intervals()
+            .map(|interval| (interval.commit().commit(), hours(interval.duration())))
+            .collect();
+
+        files
+            .into_iter()
+            .map(|(commit, files)| {
+                let shares: Vec<f64> = match strategy {
+                    Self::Interval => {
+                        let hours = intervals.get(commit.commit()).copied().unwrap_or_default();
+
+                        vec![hours / files.len() as f64; files.len()]
+                    }
+                    Self::Even => {
+                        vec![duration / changing as f64 / files.len() as f64; files.len()]
+                    }
+                    Self::Churn => files
+                        .iter()
+                        .map(|file| duration * file.loc_stats().churn() as f64 / churn as f64)
+                        .collect(),
+                };
+                let total = if files.is_empty() {
+                    match strategy {
+                        Self::Interval => intervals.get(commit.commit()).copied(),
+                        _ => None,
+                    }
+                    .unwrap_or_default()
+                } else {
+                    shares.iter().sum()
+                };
+
+                (commit.commit(), (total, shares))
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Display for Attribution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Interval => write!(f, "interval"),
+            Self::Even => write!(f, "even"),
+            Self::Churn => write!(f, "churn"),
+        }
+    }
+}
+
+/// The things to attribute the working time to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    /// Individual files, by their latest path.
+    File,
+
+    /// Directories, up to a certain depth.
+    Directory,
+
+    /// The languages of the files, see `crate::language`.
+    Language,
+}
+
+impl Level {
+    /// The column title of this level.
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::File => "File",
+            Self::Directory => "Directory",
+            Self::Language => "Language",
+        }
+    }
+}
+
+/// The working time attributed to a file, directory or language.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct TimeShare {
+    /// The path of the file or directory or the name of the language.
+    name: String,
+
+    /// The number of commits changing it.
+    commits: usize,
+
+    /// The attributed working time in hours.
+    hours: f64,
+
+    /// The share of the estimated working time, between 0 and 1.
+    share: f64,
+}
+
+impl TimeShare {
+    /// Attributes the working time of the given sessions to the files matching
+    /// the given filter and aggregates it on the given level.
+    ///
+    /// Renamed files are tracked by their latest path, directories are cut
+    /// after the given number of components. The time of commits without any
+    /// matching files is listed as `crate::group::NO_FILES`, such that the
+    /// shares add up to the whole estimated time. The entries are sorted by
+    /// their hours, descending.
+    pub fn attribute(
+        sessions: &[Session],
+        filter: &crate::Filter,
+        timezone: Timezone,
+        attribution: Attribution,
+        level: Level,
+        depth: usize,
+    ) -> Vec<Self> {
+        let total: f64 = sessions.iter().map(|s| hours(s.duration())).sum();
+        let renames = Renames::new(
+            sessions
+                .iter()
+                .rev()
+                .flat_map(|session| session.commits().iter().rev().copied()),
+        );
+        let mut shares: HashMap<String, (BTreeSet<&str>, f64)> = HashMap::new();
+
+        for record in Record::collect(sessions, filter, timezone, true, attribution) {
+            let name = match (record.file(), level) {
+                (None, _) => crate::group::NO_FILES.to_owned(),
+                (Some(file), Level::File) => renames.resolve(file),
+                (Some(file), Level::Directory) => {
+                    crate::hotspots::directory(&renames.resolve(file), depth)
+                }
+                (Some(file), Level::Language) => filter.language(file).to_owned(),
+            };
+            let (commits, hours) = shares.entry(name).or_default();
+
+            commits.insert(record.commit().commit());
+            *hours += record.hours();
+        }
+
+        let mut shares: Vec<Self> = shares
+            .into_iter()
+            .map(|(name, (commits, hours))| Self {
+                name,
+                commits: commits.len(),
+                hours,
+                share: if total > 0.0 { hours / total } else { 0.0 },
+            })
+            .collect();
+
+        shares.sort_by(|a, b| {
+            b.hours
+                .total_cmp(&a.hours)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        shares
+    }
+
+    /// The getter method for the field `commits` of the corresponding struct.
+    pub fn commits(&self) -> usize {
+        self.commits
+    }
+
+    /// The getter method for the field `hours` of the corresponding struct.
+    pub fn hours(&self) -> f64 {
+        self.hours
+    }
+
+    /// The getter method for the field `name` of the corresponding struct.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The getter method for the field `share` of the corresponding struct.
+    pub fn share(&self) -> f64 {
+        self.share
+    }
+
+    /// Converts the given shares of the given level into a table.
+    pub fn to_table(shares: &[Self], level: Level) -> Table {
+        let mut table = Table::new(&[
+            (level.title(), Alignment::Left),
+            ("Commits", Alignment::Right),
+            ("Hours", Alignment::Right),
+            ("Share", Alignment::Right),
+        ]);
+
+        for share in shares {
+            table.push(vec![
+                share.name().to_owned(),
+                share.commits().to_string(),
+                format!("{:.2}", share.hours()),
+                format!("{:.1}%", share.share() * 100.0),
+            ]);
+        }
+
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Attribution, Level, TimeShare};
+    use crate::session::Session;
+
+    /// A session whose second commit does not change any files.
+    const LOG: &str = "commit 2222222222222222222222222222222222222222
+Author: Alice <alice@example.com>
+Date:   Wed Jun 1 10:00:00 2022 +0000
+
+    Merge the library
+
+commit 1111111111111111111111111111111111111111
+Author: Alice <alice@example.com>
+Date:   Wed Jun 1 09:00:00 2022 +0000
+
+    Add the library
+
+1\t0\tsrc/lib.rs
+";
+
+    #[test]
+    fn time_without_files_is_kept() {
+        let (commits, _) = crate::Commit::parse_log(LOG);
+        let sessions = Session::detect(commits.iter().rev(), 3);
+        let shares = |attribution| {
+            TimeShare::attribute(
+                &sessions,
+                &crate::Filter::default(),
+                crate::timezone::Timezone::Utc,
+                attribution,
+                Level::File,
+                1,
+            )
+            .iter()
+            .map(|share| (share.name().to_owned(), share.hours()))
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            shares(Attribution::Interval),
+            [
+                ("(no files)".to_owned(), 1.0),
+                ("src/lib.rs".to_owned(), 0.0)
+            ]
+        );
+        assert_eq!(shares(Attribution::Even), [("src/lib.rs".to_owned(), 1.0)]);
+    }
+}
@@ -0,0 +1,499 @@
+//! The summary of the work per author.
+//!
+//! Authors often commit with several names, e.g. from different machines.
+//! Identities sharing an email address, ignoring its case, are hence
+//! considered the same canonical author. Merging identities which share a name
+//! is optional, since different people may have the same name and generic
+//! names like `root` are common.
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{
+    loc::{LocMetric, LocStats},
+    session::hours,
+    session::Session,
+    timezone::Timezone,
+};
+
+/// The aggregates of a single canonical author.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct AuthorSummary {
+    /// The most frequently used name.
+    name: String,
+
+    /// The email addresses the author committed with, in lower case.
+    emails: Vec<String>,
+
+    /// The number of calendar days with at least one commit.
+    active_days: usize,
+
+    /// The number of commits.
+    commits: usize,
+
+    /// The date of the first commit.
+    first_commit: chrono::DateTime<chrono::FixedOffset>,
+
+    /// The date of the last commit.
+    last_commit: chrono::DateTime<chrono::FixedOffset>,
+
+    /// The working time in hours.
+    ///
+    /// The time between two commits of a session is attributed to the author
+    /// of the later one.
+    hours: f64,
+
+    /// The inserted and deleted lines.
+    loc: LocStats,
+
+    /// The number of sessions the author contributed to.
+    sessions: usize,
+
+    /// The author's share of the total working time, between 0 and 1.
+    share: f64,
+}
+
+impl AuthorSummary {
+    /// The getter method for the field `active_days` of the corresponding
+    /// struct.
+    pub fn active_days(&self) -> usize {
+        self.active_days
+    }
+
+    /// The getter method for the field `commits` of the corresponding struct.
+    pub fn commits(&self) -> usize {
+        self.commits
+    }
+
+    /// The getter method for the field `emails` of the corresponding struct.
+    pub fn emails(&self) -> &[String] {
+        &self.emails
+    }
+
+    /// The getter method for the field `first_commit` of the corresponding
+    /// struct.
+    pub fn first_commit(&self) -> &chrono::DateTime<chrono::FixedOffset> {
+        &self.first_commit
+    }
+
+    /// The getter method for the field `hours` of the corresponding struct.
+    pub fn hours(&self) -> f64 {
+        self.hours
+    }
+
+    /// The getter method for the field `last_commit` of the corresponding
+    /// struct.
+    pub fn last_commit(&self) -> &chrono::DateTime<chrono::FixedOffset> {
+        &self.last_commit
+    }
+
+    /// The getter method for the field `loc` of the corresponding struct.
+    pub fn loc(&self) -> LocStats {
+        self.loc
+    }
+
+    /// The getter method for the field `name` of the corresponding struct.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The getter method for the field `sessions` of the corresponding struct.
+    pub fn sessions(&self) -> usize {
+        self.sessions
+    }
+
+    /// The getter method for the field `share` of the corresponding struct.
+    pub fn share(&self) -> f64 {
+        self.share
+    }
+
+    /// Summarises the commits and the working time of every canonical author
+    /// in the given sessions.
+    ///
+    /// The dates are converted into the given time zone, which also determines
+    /// the active days. See `CanonicalNames` for how the identities are
+    /// merged. The summaries are sorted by the given key, which sorts the
+    /// lines by the given metric.
+    pub fn summarise(
+        sessions: &[Session],
+        filter: &crate::Filter,
+        timezone: Timezone,
+        sort_by: SortKey,
+        metric: LocMetric,
+    ) -> Vec<Self> {
+        let names = CanonicalNames::new(sessions, filter);
+        let mut builders: HashMap<usize, Builder> = HashMap::new();
+        for session in sessions {
+            let mut contributors = BTreeSet::new();
+
+            for commit in session.commits() {
+                let id = names.identities.canonical(commit.author());
+                let date = timezone.convert(commit.date());
+                let builder = builders
+                    .entry(id)
+                    .or_insert_with(|| Builder::new(names.get(commit.author()), date));
+
+                builder
+                    .emails
+                    .insert(commit.author().email().to_lowercase());
+                builder.days.insert(date.naive_local().date());
+                builder.commits += 1;
+                builder.first_commit = builder.first_commit.min(date);
+                builder.last_commit = builder.last_commit.max(date);
+                builder.loc += commit.loc_stats(filter);
+                contributors.insert(id);
+            }
+            for interval in session.intervals() {
+                let id = names.identities.canonical(interval.commit().author());
+
+                builders.get_mut(&id).unwrap().work += hours(interval.duration());
+            }
+            for id in contributors {
+                builders.get_mut(&id).unwrap().sessions += 1;
+            }
+        }
+
+        let total: f64 = builders.values().map(|b| b.work).sum();
+        let mut authors: Vec<Self> = builders
+            .into_values()
+            .map(|builder| builder.build(total))
+            .collect();
+
+        sort_by.sort(&mut authors, metric);
+        authors
+    }
+}
+
+/// The columns to sort the author summaries by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum SortKey {
+    /// The name, in alphabetical order.
+    Name,
+
+    /// The working time.
+    Hours,
+
+    /// The share of the total working time, the same as `hours`.
+    Share,
+
+    /// The number of sessions.
+    Sessions,
+
+    /// The number of commits.
+    Commits,
+
+    /// The number of active days.
+    ActiveDays,
+
+    /// The number of inserted lines.
+    Added,
+
+    /// The number of deleted lines.
+    Removed,
+
+    /// The lines as counted by the configured metric, see `LocMetric`.
+    Loc,
+
+    /// The date of the first commit.
+    FirstCommit,
+
+    /// The date of the last commit.
+    LastCommit,
+}
+
+impl SortKey {
+    /// Sorts the given summaries by this key.
+    ///
+    /// Names are sorted alphabetically, all other columns descending, such
+    /// that the most active or most recent authors come first. `Loc` sorts by
+    /// the given metric. Ties are sorted by name.
+    pub fn sort(self, authors: &mut [AuthorSummary], metric: LocMetric) {
+        authors.sort_by(|a, b| {
+            let ordering = match self {
+                Self::Name => std::cmp::Ordering::Equal,
+                Self::Hours | Self::Share => b.hours.total_cmp(&a.hours),
+                Self::Sessions => b.sessions.cmp(&a.sessions),
+                Self::Commits => b.commits.cmp(&a.commits),
+                Self::ActiveDays => b.active_days.cmp(&a.active_days),
+                Self::Added => b.loc.added().cmp(&a.loc.added()),
+                Self::Removed => b.loc.removed().cmp(&a.loc.removed()),
+                Self::Loc => b.loc.get(metric).cmp(&a.loc.get(metric)),
+                Self::FirstCommit => b.first_commit.cmp(&a.first_commit),
+                Self::LastCommit => b.last_commit.cmp(&a.last_commit),
+            };
+
+            ordering.then_with(|| a.name.cmp(&b.name))
+        });
+    }
+}
+
+/// The partial aggregates of a canonical author.
+struct Builder<'a> {
+    /// The number of commits.
+    commits: usize,
+
+    /// The calendar days with at least one commit.
+    days: BTreeSet<chrono::NaiveDate>,
+
+    /// The email addresses, in lower case.
+    emails: BTreeSet<String>,
+
+    /// The date of the first commit.
+    first_commit: chrono::DateTime<chrono::FixedOffset>,
+
+    /// The date of the last commit.
+    last_commit: chrono::DateTime<chrono::FixedOffset>,
+
+    /// The inserted and deleted lines.
+    loc: LocStats,
+
+    /// The canonical name.
+    name: &'a str,
+
+    /// The number of sessions.
+    sessions: usize,
+
+    /// The working time in hours.
+    work: f64,
+}
+
+impl<'a> Builder<'a> {
+    /// Creates an empty builder for an author with the given canonical name
+    /// who committed at the given date.
+    fn new(name: &'a str, date: chrono::DateTime<chrono::FixedOffset>) -> Self {
+        Self {
+            commits: 0,
+            days: BTreeSet::new(),
+            emails: BTreeSet::new(),
+            first_commit: date,
+            last_commit: date,
+            loc: LocStats::default(),
+            name,
+            sessions: 0,
+            work: 0.0,
+        }
+    }
+
+    /// Finishes the summary, given the total working time of all authors.
+    fn build(self, total: f64) -> AuthorSummary {
+        AuthorSummary {
+            name: self.name.to_owned(),
+            emails: self.emails.into_iter().collect(),
+            active_days: self.days.len(),
+            commits: self.commits,
+            first_commit: self.first_commit,
+            last_commit: self.last_commit,
+            hours: self.work,
+            loc: self.loc,
+            sessions: self.sessions,
+            share: if total > 0.0 { self.work / total } else { 0.0 },
+        }
+    }
+}
+
+/// The canonical names of the authors of some sessions.
+///
+/// Identities sharing an email address, ignoring its case, belong to the same
+/// canonical author, as do identities sharing a name if the filter says so.
+/// The name of a canonical author is the one used by most of their commits.
+#[derive(Default)]
+pub struct CanonicalNames {
+    /// The canonical authors of the identities.
+    identities: Identities,
+
+    /// The name of every canonical author.
+    names: HashMap<usize, String>,
+}
+
+impl CanonicalNames {
+    /// Resolves the authors of the commits of the given sessions.
+    pub fn new(sessions: &[Session], filter: &crate::Filter) -> Self {
+        let mut identities = Identities {
+            merge_names: filter.merge_author_names(),
+            ..Identities::default()
+        };
+        let commits = || sessions.iter().flat_map(|session| session.commits());
+
+        for commit in commits() {
+            identities.add(commit.author());
+        }
+
+        let mut counts: HashMap<usize, HashMap<&str, usize>> = HashMap::new();
+        for commit in commits() {
+            let id = identities.canonical(commit.author());
+
+            *counts
+                .entry(id)
+                .or_default()
+                .entry(commit.author().name())
+                .or_default() += 1;
+        }
+
+        let names = counts
+            .into_iter()
+            .map(|(id, counts)| {
+                let name = counts
+                    .into_iter()
+                    .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+                    .map(|(name, _)| name.to_owned())
+                    .unwrap_or_default();
+
+                (id, name)
+            })
+            .collect();
+
+        Self { identities, names }
+    }
+
+    /// The canonical name of the given author.
+    ///
+    /// Authors of commits which were not passed to `CanonicalNames::new`
+    /// keep their name.
+    pub fn get<'a>(&'a self, author: &'a crate::Author) -> &'a str {
+        self.identities
+            .emails
+            .get(&author.email().to_lowercase())
+            .and_then(|id| self.names.get(&self.identities.root(*id)))
+            .map_or_else(|| author.name(), String::as_str)
+    }
+}
+
+/// The assignment of author identities to canonical authors.
+///
+/// This is a union-find structure over all names and email addresses.
+#[derive(Default)]
+struct Identities {
+    /// The identifiers of the known email addresses, in lower case.
+    emails: HashMap<String, usize>,
+
+    /// Whether identities sharing a name are merged.
+    merge_names: bool,
+
+    /// The identifiers of the known names.
+    names: HashMap<String, usize>,
+
+    /// The parent of every identifier, roots are their own parent.
+    parents: Vec<usize>,
+}
+
+impl Identities {
+    /// Registers the given identity, merging it with any known one sharing its
+    /// email address or, if configured, its name.
+    fn add(&mut self, author: &crate::Author) {
+        let email = self.id(author.email().to_lowercase(), true);
+        if !self.merge_names {
+            return;
+        }
+        let name = self.id(author.name().to_owned(), false);
+        let (email, name) = (self.root(email), self.root(name));
+
+        self.parents[email.max(name)] = email.min(name);
+    }
+
+    /// The identifier of the canonical author of the given identity.
+    ///
+    /// The identity needs to be registered before.
+    fn canonical(&self, author: &crate::Author) -> usize {
+        self.root(self.emails[&author.email().to_lowercase()])
+    }
+
+    /// The identifier of the given name or email address, creating a new one
+    /// if it is unknown.
+    fn id(&mut self, key: String, is_email: bool) -> usize {
+        let next = self.parents.len();
+        let map = if is_email {
+            &mut self.emails
+        } else {
+            &mut self.names
+        };
+        let id = *map.entry(key).or_insert(next);
+
+        if id == next {
+            self.parents.push(next);
+        }
+        id
+    }
+
+    /// The root of the tree the given identifier belongs to.
+    fn root(&self, mut id: usize) -> usize {
+        while self.parents[id] != id {
+            id = self.parents[id];
+        }
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AuthorSummary, Identities, SortKey};
+    use crate::loc::LocMetric;
+
+    fn author(author: &str) -> crate::Author {
+        crate::Author::parse(author).unwrap()
+    }
+
+    #[test]
+    fn identities_merge_emails_ignoring_the_case() {
+        let mut identities = Identities::default();
+        let (first, second) = (author("Jane <jane@a.org>"), author("J. D. <JANE@a.org>"));
+
+        identities.add(&first);
+        identities.add(&second);
+
+        assert_eq!(identities.canonical(&first), identities.canonical(&second));
+    }
+
+    #[test]
+    fn identities_merge_names_only_if_configured() {
+        let (first, second) = (author("root <a@host>"), author("root <b@host>"));
+
+        for merge_names in [false, true] {
+            let mut identities = Identities {
+                merge_names,
+                ..Identities::default()
+            };
+
+            identities.add(&first);
+            identities.add(&second);
+
+            assert_eq!(
+                identities.canonical(&first) == identities.canonical(&second),
+                merge_names
+            );
+        }
+    }
+
+    #[test]
+    fn loc_sorts_by_the_metric() {
+        let (commits, _) = crate::Commit::parse_log(
+            "commit 2222222222222222222222222222222222222222
+Author: Bob <bob@example.com>
+Date:   Wed Jun 1 10:00:00 2022 +0000
+
+    Second commit
+
+2\t0\tsrc/lib.rs
+
+commit 1111111111111111111111111111111111111111
+Author: Alice <alice@example.com>
+Date:   Wed Jun 1 09:00:00 2022 +0000
+
+    First commit
+
+3\t3\tREADME.md
+",
+        );
+        let sessions = crate::session::Session::detect(commits.iter().rev(), 3);
+
+        for (metric, first) in [(LocMetric::Net, "Bob"), (LocMetric::Churn, "Alice")] {
+            let authors = AuthorSummary::summarise(
+                &sessions,
+                &crate::Filter::default(),
+                crate::timezone::Timezone::Utc,
+                SortKey::Loc,
+                metric,
+            );
+
+            assert_eq!(authors[0].name(), first);
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//! Charts of the daily activity for the terminal.
+//!
+//! The charts are drawn with Unicode blocks and ANSI colours when writing to a
+//! terminal and degrade to plain ASCII otherwise, such that they stay legible
+//! when redirected into a file or piped into another program.
+
+use std::{collections::BTreeMap, fmt::Write, io::IsTerminal};
+
+use chrono::Datelike;
+
+/// The width of the longest bar in characters.
+const BAR_WIDTH: usize = 40;
+
+/// The levels of a sparkline, from no activity to the maximum.
+const SPARKS_ASCII: [char; 8] = [' ', '.', ':', '-', '=', '+', '*', '#'];
+
+/// See `SPARKS_ASCII`.
+const SPARKS_UNICODE: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// The partial blocks of a bar in eighths of a character.
+const EIGHTHS: [&str; 8] = ["", "▏", "▎", "▍", "▌", "▋", "▊", "▉"];
+
+/// The cells of a contribution calendar, from no activity to the maximum.
+const CELLS_ASCII: [char; 5] = ['.', '-', '+', '*', '#'];
+
+/// The ANSI colours of the cells of a contribution calendar, see
+/// `CELLS_ASCII`.
+const CELLS_COLOURS: [u8; 5] = [237, 22, 28, 34, 40];
+
+/// The available terminal charts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Chart {
+    /// A single line with a character per day.
+    Sparkline,
+
+    /// A horizontal bar per period.
+    Bars,
+
+    /// A grid with a cell per day and a column per week.
+    Calendar,
+}
+
+/// The characters the charts are drawn with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Style {
+    /// Plain ASCII without any colours.
+    Ascii,
+
+    /// Unicode blocks and ANSI colours.
+    Unicode,
+}
+
+impl Style {
+    /// Chooses Unicode if `stdout` is a terminal and ASCII otherwise.
+    pub fn detect() -> Self {
+        if std::io::stdout().is_terminal() {
+            Self::Unicode
+        } else {
+            Self::Ascii
+        }
+    }
+}
+
+/// Draws a sparkline with a character per day from the first to the last of
+/// the given days.
+///
+/// Days without any activity are left blank.
+pub fn sparkline(days: &BTreeMap<chrono::NaiveDate, f64>, style: Style) -> String {
+    let (first, last) = match (days.keys().next(), days.keys().next_back()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => return String::new(),
+    };
+    let max = days.values().copied().fold(0.0, f64::max);
+    let sparks: &[char] = match style {
+        Style::Ascii => &SPARKS_ASCII,
+        Style::Unicode => &SPARKS_UNICODE,
+    };
+
+    first
+        .iter_days()
+        .take_while(|date| *date <= last)
+        .map(|date| {
+            let value = days.get(&date).copied().unwrap_or_default();
+
+            if value <= 0.0 || max <= 0.0 {
+                sparks[0]
+            } else {
+                let levels = sparks.len() - 1;
+                sparks[((value / max * levels as f64).ceil() as usize).clamp(1, levels)]
+            }
+        })
+        .collect()
+}
+
+/// Draws a labelled horizontal bar per data point, followed by its value.
+pub fn bars(points: &[(String, f64)], unit: &str, style: Style) -> String {
+    let max = points.iter().map(|(_, v)| *v).fold(0.0, f64::max);
+    let label_width = points
+        .iter()
+        .map(|(label, _)| label.chars().count())
+        .max()
+        .unwrap_or_default();
+    let mut chart = String::new();
+
+    for (label, value) in points {
+        let ratio = if max > 0.0 { value.max(0.0) / max } else { 0.0 };
+        let bar = match style {
+            Style::Ascii => "#".repeat((ratio * BAR_WIDTH as f64).round() as usize),
+            Style::Unicode => {
+                let eighths = (ratio * (BAR_WIDTH * 8) as f64).round() as usize;
+
+                "█".repeat(eighths / 8) + EIGHTHS[eighths % 8]
+            }
+        };
+
+        let _ = writeln!(
+            chart,
+            "{:<label_width$}  {:<bar_width$}  {:.2}{}",
+            label,
+            bar,
+            value,
+            unit,
+            label_width = label_width,
+            bar_width = BAR_WIDTH
+        );
+    }
+
+    chart
+}
+
+/// Draws a contribution calendar with a row per weekday and a column per
+/// week.
+///
+/// The cells are shaded in four levels relative to the most active day. Days
+/// without any activity are drawn as the lowest level, days outside the range
+/// of the given ones are left blank.
+pub fn calendar(
+    days: &BTreeMap<chrono::NaiveDate, f64>,
+    week_start: chrono::Weekday,
+    style: Style,
+) -> String {
+    let (first, last) = match (days.keys().next(), days.keys().next_back()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => return String::new(),
+    };
+    let start = crate::period::first_day_of_week(first, week_start);
+    let weeks = ((last - start).num_days() / 7 + 1) as usize;
+    let max = days.values().copied().fold(0.0, f64::max);
+    let mut header = String::new();
+
+    for week in 0..weeks {
+        let column = 2 * week;
+        let month = (0..7)
+            .map(|day| start + chrono::Duration::days(week as i64 * 7 + day))
+            .find(|date| date.day() == 1 || (week == 0 && *date == first));
+
+        if let Some(date) = month {
+            if header.len() <= column {
+                header = format!("{:<column$}{}", header, date.format("%b"), column = column);
+            }
+        }
+    }
+    let mut chart = format!("    {}\n", header);
+
+    for weekday in 0..7 {
+        let _ = write!(
+            chart,
+            "{:<4}",
+            (start + chrono::Duration::days(weekday)).format("%a")
+        );
+
+        for week in 0..weeks {
+            let date = start + chrono::Duration::days(week as i64 * 7 + weekday);
+
+            if date < first || date > last {
+                chart.push_str("  ");
+                continue;
+            }
+
+            let value = days.get(&date).copied().unwrap_or_default();
+            let level = if value <= 0.0 || max <= 0.0 {
+                0
+            } else {
+                ((value / max * 4.0).ceil() as usize).clamp(1, 4)
+            };
+
+            match style {
+                Style::Ascii => {
+                    let _ = write!(chart, "{} ", CELLS_ASCII[level]);
+                }
+                Style::Unicode => {
+                    let _ = write!(chart, "\x1b[38;5;{}m■\x1b[0m ", CELLS_COLOURS[level]);
+                }
+            }
+        }
+        chart = chart.trim_end_matches(' ').to_owned();
+        chart.push('\n');
+    }
+
+    chart
+}
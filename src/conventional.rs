@@ -0,0 +1,194 @@
+//! The parsing of Conventional Commits headers.
+//!
+//! A conventional commit message starts with a header like
+//! `feat(parser)!: support arrays`, consisting of
+//!
+//! * the type of the change, such as `feat`, `fix` or `chore`,
+//! * an optional scope in parentheses,
+//! * an optional `!` marking a breaking change, and
+//! * a description after a colon and a space.
+//!
+//! A footer starting with `BREAKING CHANGE:` marks a breaking change, too.
+//! See <https://www.conventionalcommits.org> for the specification.
+
+use crate::{
+    attribution::Attribution,
+    group::{Dimension, Grouping, Metric},
+    session::Session,
+    timezone::Timezone,
+};
+
+/// The type of commits without a conventional header.
+pub const NO_TYPE: &str = "(none)";
+
+/// The structured header of a conventional commit message.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct Header {
+    /// The type of the change, in lower case.
+    kind: String,
+
+    /// The part of the code base the change affects.
+    scope: Option<String>,
+
+    /// Whether the change breaks the compatibility.
+    breaking: bool,
+
+    /// The summary of the change.
+    description: String,
+}
+
+impl Header {
+    /// The getter method for the field `breaking` of the corresponding
+    /// struct.
+    pub fn breaking(&self) -> bool {
+        self.breaking
+    }
+
+    /// The getter method for the field `description` of the corresponding
+    /// struct.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// The getter method for the field `kind` of the corresponding struct.
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    /// Extracts the header from the given commit message.
+    ///
+    /// Messages whose first line does not follow the specification do not
+    /// have a header.
+    pub fn parse(message: &str) -> Option<Self> {
+        let subject = message.lines().next().unwrap_or_default().trim();
+        let (prefix, description) = subject.split_once(": ")?;
+        let description = description.trim();
+        let (prefix, mut breaking) = match prefix.strip_suffix('!') {
+            Some(prefix) => (prefix, true),
+            None => (prefix, false),
+        };
+        let (kind, scope) = match prefix.split_once('(') {
+            Some((kind, scope)) => {
+                let scope = scope.strip_suffix(')')?.trim();
+
+                if scope.is_empty() || scope.contains(['(', ')']) {
+                    return None;
+                }
+                (kind, Some(scope.to_owned()))
+            }
+            None => (prefix, None),
+        };
+
+        if kind.is_empty()
+            || description.is_empty()
+            || !kind.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            return None;
+        }
+        breaking |= message.lines().skip(1).any(|line| {
+            line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:")
+        });
+
+        Some(Self {
+            kind: kind.to_lowercase(),
+            scope,
+            breaking,
+            description: description.to_owned(),
+        })
+    }
+
+    /// The getter method for the field `scope` of the corresponding struct.
+    pub fn scope(&self) -> Option<&str> {
+        self.scope.as_deref()
+    }
+}
+
+/// Aggregates the commits, LOC and working time per type of change.
+///
+/// Commits without a conventional header have the type `NO_TYPE`. See
+/// `Attribution` for how the working time is attributed. The types are
+/// sorted by their hours, descending.
+pub fn breakdown(
+    sessions: &[Session],
+    filter: &crate::Filter,
+    timezone: Timezone,
+    attribution: Attribution,
+    repository: &str,
+) -> Grouping {
+    let mut grouping = Grouping::new(
+        &[Dimension::Type],
+        &[
+            Metric::Commits,
+            Metric::Added,
+            Metric::Removed,
+            Metric::Churn,
+            Metric::Hours,
+        ],
+        sessions,
+        filter,
+        timezone,
+        attribution,
+        repository,
+    );
+
+    grouping.sort_by(Metric::Hours);
+    grouping
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Header;
+
+    #[test]
+    fn parse_reads_type_scope_and_breaking_marks() {
+        let header = Header::parse("Feat(parser)!: support arrays\n\nBody").unwrap();
+
+        assert_eq!(header.kind(), "feat");
+        assert_eq!(header.scope(), Some("parser"));
+        assert!(header.breaking());
+        assert_eq!(header.description(), "support arrays");
+
+        let header = Header::parse("fix: handle empty input\n\nBREAKING CHANGE: none").unwrap();
+        assert_eq!(header.scope(), None);
+        assert!(header.breaking());
+        assert!(!Header::parse("docs: typo").unwrap().breaking());
+    }
+
+    #[test]
+    fn parse_rejects_other_subjects() {
+        for message in [
+            "Fix the parser",
+            "fix:missing space",
+            "fix: ",
+            ": no type",
+            "fix(): empty scope",
+            "fix(a(b)): nested scope",
+            "fix(parser: unclosed scope",
+            "fix it: spaces in the type",
+        ] {
+            assert_eq!(Header::parse(message), None, "{}", message);
+        }
+    }
+
+    #[test]
+    fn breakdown_labels_commits_without_a_header() {
+        let (commits, _) = crate::Commit::parse_log(
+            "commit 1111111111111111111111111111111111111111
+Author: Alice <alice@example.com>
+Date:   Wed Jun 1 09:00:00 2022 +0000
+
+    Update things
+",
+        );
+        let sessions = crate::session::Session::detect(commits.iter().rev(), 3);
+        let types = super::breakdown(
+            &sessions,
+            &crate::Filter::default(),
+            crate::timezone::Timezone::Utc,
+            crate::attribution::Attribution::Even,
+            "repo",
+        );
+
+        assert_eq!(types.rows()[0][0], super::NO_TYPE);
+    }
+}
@@ -0,0 +1,363 @@
+//! The logical coupling of files which change together.
+//!
+//! Two files are coupled if they keep being changed in the same commits,
+//! regardless of whether they reference each other. For every pair, the
+//! analysis reports
+//!
+//! * the support, the share of all commits changing both,
+//! * the confidence, the share of the commits changing one file which also
+//!   change the other, in both directions, and
+//! * the lift, how much more often both change together than expected if
+//!   they were independent.
+//!
+//! Huge commits, such as reformattings or dependency updates, couple every
+//! file with every other one and are hence skipped.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Write,
+};
+
+use crate::{
+    hotspots::Renames,
+    session::Session,
+    table::{Alignment, Table},
+    Commit,
+};
+
+/// The paths to analyse the coupling of.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum CouplingLevel {
+    /// Individual files.
+    File,
+
+    /// Directories, up to a certain depth.
+    Directory,
+}
+
+/// The thresholds to drop noise from the coupling analysis.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Thresholds {
+    /// The maximum number of files a commit may change to be considered,
+    /// counted before collapsing them into directories.
+    pub max_files: usize,
+
+    /// The minimum number of commits changing both paths of a pair.
+    pub min_commits: usize,
+
+    /// The minimum confidence of a pair in at least one direction.
+    pub min_confidence: f64,
+}
+
+/// The minimum confidence of a coupled pair, between 0 and 1.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Confidence(f64);
+
+impl From<Confidence> for f64 {
+    fn from(confidence: Confidence) -> Self {
+        confidence.0
+    }
+}
+
+impl std::fmt::Display for Confidence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::str::FromStr for Confidence {
+    type Err = ConfidenceParseError;
+
+    fn from_str(confidence: &str) -> Result<Self, Self::Err> {
+        let confidence: f64 = confidence
+            .parse()
+            .map_err(|_| ConfidenceParseError::NotANumber)?;
+
+        if (0.0..=1.0).contains(&confidence) {
+            Ok(Self(confidence))
+        } else {
+            Err(ConfidenceParseError::OutOfRange)
+        }
+    }
+}
+
+/// The set of errors which may occur.
+#[derive(Debug)]
+pub enum ConfidenceParseError {
+    /// The confidence is not a number.
+    NotANumber,
+
+    /// The confidence is less than 0 or greater than 1.
+    OutOfRange,
+}
+
+impl std::fmt::Display for ConfidenceParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotANumber => write!(f, "expected a number between 0 and 1"),
+            Self::OutOfRange => write!(f, "the confidence must be between 0 and 1"),
+        }
+    }
+}
+
+impl std::error::Error for ConfidenceParseError {}
+
+/// The coupling of a pair of paths.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Coupling {
+    /// The first path, in alphabetical order.
+    first: String,
+
+    /// The second path, in alphabetical order.
+    second: String,
+
+    /// The number of commits changing both paths.
+    commits: usize,
+
+    /// The share of all considered commits changing both paths.
+    support: f64,
+
+    /// The share of the commits changing the first path which also change the
+    /// second one.
+    confidence_first: f64,
+
+    /// The share of the commits changing the second path which also change
+    /// the first one.
+    confidence_second: f64,
+
+    /// The ratio of the observed to the expected number of commits changing
+    /// both paths.
+    lift: f64,
+}
+
+impl Coupling {
+    /// Analyses the coupling of all pairs of paths changed by the given
+    /// sessions.
+    ///
+    /// Only files matching the given filter are considered. Renamed files are
+    /// tracked by their latest path, directories are cut after the given
+    /// number of components. The pairs are sorted by the number of commits
+    /// changing both paths and their confidence, descending.
+    pub fn analyse(
+        sessions: &[Session],
+        filter: &crate::Filter,
+        level: CouplingLevel,
+        depth: usize,
+        thresholds: Thresholds,
+    ) -> Vec<Self> {
+        let commits: Vec<&Commit> = sessions
+            .iter()
+            .flat_map(|session| session.commits().iter().copied())
+            .collect();
+        let renames = Renames::new(commits.iter().rev().copied());
+        let changes: Vec<BTreeSet<String>> = commits
+            .iter()
+            .filter_map(|commit| {
+                let files: BTreeSet<String> = commit
+                    .locs()
+                    .iter()
+                    .filter(|loc| filter.check_loc(loc))
+                    .map(|loc| renames.resolve(loc))
+                    .collect();
+
+                if files.is_empty() || files.len() > thresholds.max_files {
+                    return None;
+                }
+
+                Some(match level {
+                    CouplingLevel::File => files,
+                    CouplingLevel::Directory => files
+                        .iter()
+                        .map(|path| crate::hotspots::directory(path, depth))
+                        .collect(),
+                })
+            })
+            .collect();
+
+        let mut singles: HashMap<&str, usize> = HashMap::new();
+        let mut pairs: HashMap<(&str, &str), usize> = HashMap::new();
+        for paths in &changes {
+            for (index, first) in paths.iter().enumerate() {
+                *singles.entry(first).or_default() += 1;
+
+                for second in paths.iter().skip(index + 1) {
+                    *pairs.entry((first, second)).or_default() += 1;
+                }
+            }
+        }
+
+        let total = changes.len() as f64;
+        let mut couplings: Vec<Self> = pairs
+            .into_iter()
+            .filter(|(_, commits)| *commits >= thresholds.min_commits)
+            .map(|((first, second), commits)| {
+                let (n_first, n_second) = (singles[first] as f64, singles[second] as f64);
+                let both = commits as f64;
+
+                Self {
+                    first: first.to_owned(),
+                    second: second.to_owned(),
+                    commits,
+                    support: both / total,
+                    confidence_first: both / n_first,
+                    confidence_second: both / n_second,
+                    lift: both * total / (n_first * n_second),
+                }
+            })
+            .filter(|coupling| coupling.confidence() >= thresholds.min_confidence)
+            .collect();
+
+        couplings.sort_by(|a, b| {
+            b.commits
+                .cmp(&a.commits)
+                .then_with(|| b.confidence().total_cmp(&a.confidence()))
+                .then_with(|| (&a.first, &a.second).cmp(&(&b.first, &b.second)))
+        });
+        couplings
+    }
+
+    /// The getter method for the field `commits` of the corresponding struct.
+    pub fn commits(&self) -> usize {
+        self.commits
+    }
+
+    /// The higher confidence of both directions.
+    pub fn confidence(&self) -> f64 {
+        self.confidence_first.max(self.confidence_second)
+    }
+
+    /// The getter method for the field `confidence_first` of the
+    /// corresponding struct.
+    pub fn confidence_first(&self) -> f64 {
+        self.confidence_first
+    }
+
+    /// The getter method for the field `confidence_second` of the
+    /// corresponding struct.
+    pub fn confidence_second(&self) -> f64 {
+        self.confidence_second
+    }
+
+    /// The getter method for the field `first` of the corresponding struct.
+    pub fn first(&self) -> &str {
+        &self.first
+    }
+
+    /// The getter method for the field `lift` of the corresponding struct.
+    pub fn lift(&self) -> f64 {
+        self.lift
+    }
+
+    /// The getter method for the field `second` of the corresponding struct.
+    pub fn second(&self) -> &str {
+        &self.second
+    }
+
+    /// The getter method for the field `support` of the corresponding struct.
+    pub fn support(&self) -> f64 {
+        self.support
+    }
+
+    /// Converts the given pairs into a table.
+    pub fn to_table(pairs: &[Self]) -> Table {
+        let mut table = Table::new(&[
+            ("First", Alignment::Left),
+            ("Second", Alignment::Left),
+            ("Commits", Alignment::Right),
+            ("Support", Alignment::Right),
+            ("Confidence (First)", Alignment::Right),
+            ("Confidence (Second)", Alignment::Right),
+            ("Lift", Alignment::Right),
+        ]);
+
+        for pair in pairs {
+            table.push(vec![
+                pair.first().to_owned(),
+                pair.second().to_owned(),
+                pair.commits().to_string(),
+                format!("{:.1}%", pair.support() * 100.0),
+                format!("{:.1}%", pair.confidence_first() * 100.0),
+                format!("{:.1}%", pair.confidence_second() * 100.0),
+                format!("{:.2}", pair.lift()),
+            ]);
+        }
+
+        table
+    }
+}
+
+/// Renders the given pairs as an undirected Graphviz graph.
+///
+/// The edges are labelled with the number of commits changing both paths and
+/// drawn the thicker the higher their confidence.
+pub fn dot(couplings: &[Coupling]) -> String {
+    let quote = |text: &str| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""));
+    let mut dot = String::from("graph coupling {\n    node [shape=box];\n");
+
+    for coupling in couplings {
+        let _ = writeln!(
+            dot,
+            "    {} -- {} [label=\"{}\", penwidth={:.2}];",
+            quote(&coupling.first),
+            quote(&coupling.second),
+            coupling.commits,
+            1.0 + 4.0 * coupling.confidence()
+        );
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Confidence, Coupling, CouplingLevel, Thresholds};
+    use crate::session::Session;
+
+    /// A commit changing three files in two directories.
+    const LOG: &str = "commit 1111111111111111111111111111111111111111
+Author: Alice <alice@example.com>
+Date:   Wed Jun 1 09:00:00 2022 +0000
+
+    Touch everything
+
+1\t0\tsrc/a.rs
+1\t0\tsrc/b.rs
+1\t0\ttests/c.rs
+";
+
+    #[test]
+    fn confidence_must_be_between_zero_and_one() {
+        for input in ["0", "0.5", "1"] {
+            let expected: f64 = input.parse().unwrap();
+
+            assert_eq!(f64::from(input.parse::<Confidence>().unwrap()), expected);
+        }
+        for input in ["-0.1", "1.5", "NaN", "half"] {
+            assert!(input.parse::<Confidence>().is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn max_files_counts_the_files_before_collapsing() {
+        let (commits, _) = crate::Commit::parse_log(LOG);
+        let sessions = Session::detect(commits.iter().rev(), 3);
+        let analyse = |max_files| {
+            Coupling::analyse(
+                &sessions,
+                &crate::Filter::default(),
+                CouplingLevel::Directory,
+                1,
+                Thresholds {
+                    max_files,
+                    min_commits: 1,
+                    min_confidence: 0.0,
+                },
+            )
+            .len()
+        };
+
+        assert_eq!(analyse(2), 0);
+        assert_eq!(analyse(3), 1);
+    }
+}
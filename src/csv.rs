@@ -0,0 +1,548 @@
+//! The export of aggregates, groups, hotspots, tickets, commits, sessions,
+//! punch cards and query results as CSV files.
+//!
+//! The files follow RFC 4180: records end with CRLF and fields containing the
+//! delimiter, a double quote or a line break are wrapped in double quotes,
+//! with contained double quotes doubled.
+
+use std::{borrow::Cow, collections::BTreeMap, io::Write};
+
+use crate::{
+    activity::Activity,
+    period::{Calendar, Period},
+    session::{hours, Session},
+    timezone::Timezone,
+};
+
+/// A column of the output file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Column {
+    /// The first day of the period or the date of the commit.
+    Date,
+
+    /// The name of the period the row belongs to.
+    Period,
+
+    /// The first day of the period.
+    Start,
+
+    /// The last day of the period.
+    End,
+
+    /// The commit hash, only available per commit.
+    Commit,
+
+    /// The author's name, only available per commit.
+    Author,
+
+    /// The author's email address, only available per commit.
+    Email,
+
+    /// The first line of the commit message, only available per commit.
+    Subject,
+
+    /// The number of commits.
+    Commits,
+
+    /// The number of inserted lines.
+    Added,
+
+    /// The number of deleted lines.
+    Removed,
+
+    /// The LOC diff, i.e. the inserted minus the deleted lines, titled `Loc`
+    /// as in the original output file.
+    Net,
+
+    /// The gross churn, i.e. the inserted plus the deleted lines.
+    Churn,
+
+    /// The estimated working time in hours.
+    Hours,
+
+    /// The names of the committing authors.
+    Authors,
+
+    /// The number of changed binary files.
+    BinaryFiles,
+}
+
+impl Column {
+    /// The default columns when writing one row per period.
+    ///
+    /// Days are identified by their date, longer periods by their name and
+    /// their first and last day.
+    pub fn per_period(calendar: &Calendar) -> Vec<Self> {
+        if calendar.is_daily() {
+            vec![Self::Date, Self::Commits, Self::Net, Self::Hours]
+        } else {
+            vec![
+                Self::Period,
+                Self::Start,
+                Self::End,
+                Self::Commits,
+                Self::Net,
+                Self::Hours,
+            ]
+        }
+    }
+
+    /// The default columns when writing one row per commit.
+    pub fn per_commit() -> Vec<Self> {
+        vec![
+            Self::Commit,
+            Self::Date,
+            Self::Author,
+            Self::Email,
+            Self::Subject,
+            Self::Added,
+            Self::Removed,
+            Self::Net,
+            Self::Hours,
+        ]
+    }
+
+    /// Whether this column describes a single commit.
+    pub fn is_commit_only(self) -> bool {
+        matches!(
+            self,
+            Self::Commit | Self::Author | Self::Email | Self::Subject
+        )
+    }
+
+    /// The title of this column in the header.
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::Date => "Date",
+            Self::Period => "Period",
+            Self::Start => "Start",
+            Self::End => "End",
+            Self::Commit => "Commit",
+            Self::Author => "Author",
+            Self::Email => "Email",
+            Self::Subject => "Subject",
+            Self::Commits => "Commits",
+            Self::Added => "Added",
+            Self::Removed => "Removed",
+            Self::Net => "Loc",
+            Self::Churn => "Churn",
+            Self::Hours => "Hours",
+            Self::Authors => "Authors",
+            Self::BinaryFiles => "Binary Files",
+        }
+    }
+}
+
+impl std::fmt::Display for Column {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match clap::ValueEnum::to_possible_value(self) {
+            Some(value) => write!(f, "{}", value.get_name()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A character separating the fields of a CSV file.
+///
+/// Double quotes and line breaks are reserved for quoting and ending records.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Delimiter(char);
+
+impl Default for Delimiter {
+    fn default() -> Self {
+        Self(',')
+    }
+}
+
+impl From<Delimiter> for char {
+    fn from(delimiter: Delimiter) -> Self {
+        delimiter.0
+    }
+}
+
+impl std::fmt::Display for Delimiter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::str::FromStr for Delimiter {
+    type Err = DelimiterParseError;
+
+    fn from_str(delimiter: &str) -> Result<Self, Self::Err> {
+        let mut chars = delimiter.chars();
+
+        match (chars.next(), chars.next()) {
+            (Some('"' | '\r' | '\n'), None) => Err(DelimiterParseError::Reserved),
+            (Some(delimiter), None) => Ok(Self(delimiter)),
+            _ => Err(DelimiterParseError::NotACharacter),
+        }
+    }
+}
+
+/// The set of errors which may occur.
+#[derive(Debug)]
+pub enum DelimiterParseError {
+    /// The delimiter is not exactly one character.
+    NotACharacter,
+
+    /// The delimiter is a double quote or a line break.
+    Reserved,
+}
+
+impl std::fmt::Display for DelimiterParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotACharacter => write!(f, "expected a single character"),
+            Self::Reserved => write!(
+                f,
+                "double quotes and line breaks cannot separate the fields"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DelimiterParseError {}
+
+/// Writes records as CSV.
+pub struct Writer<W: Write> {
+    /// The character separating two fields.
+    delimiter: char,
+
+    /// The destination.
+    inner: W,
+}
+
+impl<W: Write> Writer<W> {
+    /// Creates a new writer separating fields by the given delimiter.
+    pub fn new(inner: W, delimiter: Delimiter) -> Self {
+        Self {
+            delimiter: delimiter.into(),
+            inner,
+        }
+    }
+
+    /// Moves the destination out of this writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Flushes the destination.
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+
+    /// Writes a single record, quoting fields where necessary.
+    pub fn write_record<I, S>(&mut self, fields: I) -> std::io::Result<()>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut record = String::new();
+
+        for (index, field) in fields.into_iter().enumerate() {
+            if index > 0 {
+                record.push(self.delimiter);
+            }
+            record.push_str(&self.quote(field.as_ref()));
+        }
+        record.push_str("\r\n");
+
+        self.inner.write_all(record.as_bytes())
+    }
+
+    /// Wraps the given field in double quotes if it contains any special
+    /// characters.
+    fn quote<'a>(&self, field: &'a str) -> Cow<'a, str> {
+        if field.contains([self.delimiter, '"', '\r', '\n']) {
+            Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+        } else {
+            Cow::Borrowed(field)
+        }
+    }
+}
+
+/// Writes one row per period with the given columns.
+///
+/// A column with the working time of every given author is appended. Columns
+/// which describe a single commit are left empty.
+pub fn write_periods<W: Write>(
+    writer: &mut Writer<W>,
+    periods: &BTreeMap<Period, Activity>,
+    columns: &[Column],
+    authors: &[&str],
+) -> std::io::Result<()> {
+    writer.write_record(
+        columns
+            .iter()
+            .map(|c| c.title())
+            .chain(authors.iter().copied()),
+    )?;
+
+    for (period, activity) in periods {
+        let cells = columns.iter().map(|column| match column {
+            Column::Date | Column::Start => period.start().to_string(),
+            Column::Period => period.name().to_owned(),
+            Column::End => period.end().to_string(),
+            Column::Commit | Column::Author | Column::Email | Column::Subject => String::new(),
+            Column::Commits => activity.commits().to_string(),
+            Column::Added => activity.loc().added().to_string(),
+            Column::Removed => activity.loc().removed().to_string(),
+            Column::Net => activity.loc().net().to_string(),
+            Column::Churn => activity.loc().churn().to_string(),
+            Column::Hours => format!("{:.2}", hours(activity.work())),
+            Column::Authors => activity
+                .authors()
+                .keys()
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join("; "),
+            Column::BinaryFiles => activity.binary_files().to_string(),
+        });
+        let work = authors.iter().map(|author| {
+            let work = activity.authors().get(*author).copied();
+
+            format!("{:.2}", hours(work.unwrap_or_else(chrono::Duration::zero)))
+        });
+
+        writer.write_record(cells.chain(work))?;
+    }
+
+    writer.flush()
+}
+
+/// Writes one row per commit of the given sessions with the given columns.
+///
+/// The working time between two commits is attributed to the later one, the
+/// first commit of a session does not add any working time. The period
+/// columns refer to the period the commit was made in.
+pub fn write_commits<W: Write>(
+    writer: &mut Writer<W>,
+    sessions: &[Session],
+    filter: &crate::Filter,
+    calendar: &Calendar,
+    timezone: Timezone,
+    columns: &[Column],
+) -> std::io::Result<()> {
+    writer.write_record(columns.iter().map(|c| c.title()))?;
+
+    for session in sessions {
+        let work = std::iter::once(chrono::Duration::zero())
+            .chain(session.intervals().map(|i| i.duration()));
+
+        for (commit, work) in session.commits().iter().zip(work) {
+            let date = timezone.convert(commit.date());
+            let period = calendar.period(date.naive_local().date());
+            let loc = commit.loc_stats(filter);
+            let period_cell = |cell: fn(&Period) -> String| period.as_ref().map(cell);
+
+            writer.write_record(columns.iter().map(|column| match column {
+                Column::Date => date.to_rfc3339(),
+                Column::Period => period_cell(|p| p.name().to_owned()).unwrap_or_default(),
+                Column::Start => period_cell(|p| p.start().to_string()).unwrap_or_default(),
+                Column::End => period_cell(|p| p.end().to_string()).unwrap_or_default(),
+                Column::Commit => commit.commit().to_owned(),
+                Column::Author | Column::Authors => commit.author().name().to_owned(),
+                Column::Email => commit.author().email().to_owned(),
+                Column::Subject => commit.subject().to_owned(),
+                Column::Commits => "1".to_owned(),
+                Column::Added => loc.added().to_string(),
+                Column::Removed => loc.removed().to_string(),
+                Column::Net => loc.net().to_string(),
+                Column::Churn => loc.churn().to_string(),
+                Column::Hours => format!("{:.2}", hours(work)),
+                Column::BinaryFiles => commit.binary_files(filter).to_string(),
+            }))?;
+        }
+    }
+
+    writer.flush()
+}
+
+/// Writes one row per work session.
+pub fn write_sessions<W: Write>(
+    writer: &mut Writer<W>,
+    sessions: &[Session],
+    filter: &crate::Filter,
+    timezone: Timezone,
+) -> std::io::Result<()> {
+    writer.write_record([
+        "Start",
+        "End",
+        "Start Offset",
+        "End Offset",
+        "Hours",
+        "Authors",
+        "Commits",
+        "Added",
+        "Removed",
+        "Net",
+        "Churn",
+        "Subjects",
+    ])?;
+
+    for session in sessions {
+        let loc = session.loc(filter);
+
+        writer.write_record([
+            timezone.convert(session.start()).to_rfc3339(),
+            timezone.convert(session.end()).to_rfc3339(),
+            session.start().offset().to_string(),
+            session.end().offset().to_string(),
+            format!("{:.2}", hours(session.duration())),
+            session.authors().join("; "),
+            session.commits().len().to_string(),
+            loc.added().to_string(),
+            loc.removed().to_string(),
+            loc.net().to_string(),
+            loc.churn().to_string(),
+            session.subjects().join("; "),
+        ])?;
+    }
+
+    writer.flush()
+}
+
+/// Writes one row per weekday and hour of the day of the given punch card.
+pub fn write_punch_card<W: Write>(
+    writer: &mut Writer<W>,
+    punch_card: &crate::punchcard::PunchCard,
+) -> std::io::Result<()> {
+    writer.write_record(["Weekday", "Hour", "Commits", "Minutes"])?;
+
+    for weekday in punch_card.weekdays() {
+        for hour in 0..24 {
+            writer.write_record([
+                weekday.weekday().to_string(),
+                hour.to_string(),
+                weekday.commits()[hour].to_string(),
+                format!("{:.2}", weekday.minutes()[hour]),
+            ])?;
+        }
+    }
+
+    writer.flush()
+}
+
+/// Writes one row per file and directory of the given hotspots.
+pub fn write_hotspots<W: Write>(
+    writer: &mut Writer<W>,
+    hotspots: &crate::hotspots::Hotspots,
+) -> std::io::Result<()> {
+    writer.write_record([
+        "Kind",
+        "Path",
+        "Commits",
+        "Added",
+        "Removed",
+        "Churn",
+        "Authors",
+        "Last Modified",
+    ])?;
+
+    for (kind, list) in [
+        ("file", hotspots.files()),
+        ("directory", hotspots.directories()),
+    ] {
+        for hotspot in list {
+            writer.write_record([
+                kind.to_owned(),
+                hotspot.path().to_owned(),
+                hotspot.commits().to_string(),
+                hotspot.loc().added().to_string(),
+                hotspot.loc().removed().to_string(),
+                hotspot.loc().churn().to_string(),
+                hotspot.authors().to_string(),
+                hotspot.last_modified().to_rfc3339(),
+            ])?;
+        }
+    }
+
+    writer.flush()
+}
+
+/// Writes one row per ticket, the unreferenced work has an empty key.
+pub fn write_tickets<W: Write>(
+    writer: &mut Writer<W>,
+    tickets: &[crate::tickets::Ticket],
+) -> std::io::Result<()> {
+    writer.write_record([
+        "Ticket",
+        "Commits",
+        "Added",
+        "Removed",
+        "Hours",
+        "First Commit",
+        "Last Commit",
+    ])?;
+
+    for ticket in tickets {
+        writer.write_record([
+            ticket.key().unwrap_or_default().to_owned(),
+            ticket.commits().to_string(),
+            ticket.loc().added().to_string(),
+            ticket.loc().removed().to_string(),
+            format!("{:.2}", ticket.hours()),
+            ticket.first_commit().to_rfc3339(),
+            ticket.last_commit().to_rfc3339(),
+        ])?;
+    }
+
+    writer.flush()
+}
+
+/// Writes one row per group of the given grouping.
+pub fn write_groups<W: Write>(
+    writer: &mut Writer<W>,
+    grouping: &crate::group::Grouping,
+) -> std::io::Result<()> {
+    writer.write_record(grouping.header())?;
+
+    for row in grouping.rows() {
+        writer.write_record(row)?;
+    }
+
+    writer.flush()
+}
+
+/// Writes the rows of the given query result.
+pub fn write_query<W: Write>(
+    writer: &mut Writer<W>,
+    result: &crate::query::QueryResult,
+) -> std::io::Result<()> {
+    writer.write_record(result.columns())?;
+
+    for row in result.rows() {
+        writer.write_record(row.iter().map(ToString::to_string))?;
+    }
+
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Delimiter, Writer};
+
+    #[test]
+    fn parse_delimiter() {
+        assert_eq!(char::from(";".parse::<Delimiter>().unwrap()), ';');
+        assert_eq!(char::from("\t".parse::<Delimiter>().unwrap()), '\t');
+        for delimiter in ["\"", "\r", "\n", "", ";;"] {
+            assert!(delimiter.parse::<Delimiter>().is_err(), "{:?}", delimiter);
+        }
+    }
+
+    #[test]
+    fn write_record_quotes_special_fields() {
+        let mut writer = Writer::new(vec![], ";".parse().unwrap());
+
+        writer
+            .write_record(["a,b", "c;d", "say \"hi\"", "two\nlines"])
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(writer.into_inner()).unwrap(),
+            "a,b;\"c;d\";\"say \"\"hi\"\"\";\"two\nlines\"\r\n"
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! The explanation of how the commits were accounted for.
+//!
+//! When the estimate looks wrong, the explanation tells for every commit which
+//! filter clauses accepted or rejected it, whether the time since the previous
+//! matching commit was counted and which files contributed to the LOC diff.
+
+use crate::session::{format_duration, Gap};
+
+/// The reasons why a certain commit was counted the way it was.
+#[derive(Debug)]
+pub struct Explanation<'a> {
+    /// The verdicts of the configured filter clauses.
+    clauses: Vec<crate::Clause<'a>>,
+
+    /// The explained commit.
+    commit: &'a crate::Commit,
+
+    /// The filter used to check the LOC diff.
+    filter: &'a crate::Filter<'a>,
+
+    /// The gap to the previous matching commit, if this one matches.
+    gap: Option<Gap>,
+
+    /// The maximum duration between two commits considered spent working.
+    max_gap: u32,
+
+    /// The time zone to display the commit date in.
+    timezone: crate::timezone::Timezone,
+}
+
+impl<'a> Explanation<'a> {
+    /// Explains how each of the given commits was accounted for.
+    ///
+    /// The commits are expected in chronological order, just as for
+    /// `Session::detect`.
+    pub fn explain<I>(
+        commits: I,
+        filter: &'a crate::Filter<'a>,
+        max_gap: u32,
+        timezone: crate::timezone::Timezone,
+    ) -> Vec<Self>
+    where
+        I: IntoIterator<Item = &'a crate::Commit>,
+    {
+        let mut previous = None;
+
+        commits
+            .into_iter()
+            .map(|commit| {
+                let clauses = filter.clauses(commit);
+                let gap = if clauses.iter().all(crate::Clause::accepted) {
+                    let gap = Gap::between(previous, commit, max_gap);
+                    previous = Some(commit);
+                    Some(gap)
+                } else {
+                    None
+                };
+
+                Self {
+                    clauses,
+                    commit,
+                    filter,
+                    gap,
+                    max_gap,
+                    timezone,
+                }
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Display for Explanation<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let date = self.timezone.convert(self.commit.date());
+
+        write!(
+            f,
+            "commit {} ({}, {}",
+            self.commit.commit(),
+            date.format("%Y-%m-%d %H:%M %:z"),
+            self.commit.author().name()
+        )?;
+        if date.offset() != self.commit.date().offset() {
+            write!(f, ", recorded at {}", self.commit.date().offset())?;
+        }
+        writeln!(f, ")")?;
+        writeln!(f, "    {}", self.commit.subject())?;
+
+        if self.clauses.is_empty() {
+            writeln!(f, "  accepted since no filter clauses are configured")?;
+        }
+        for clause in &self.clauses {
+            writeln!(f, "  {}", clause)?;
+        }
+
+        let gap = match self.gap {
+            Some(gap) => gap,
+            None => return writeln!(f, "  not counted since the filter rejected it"),
+        };
+        match gap {
+            Gap::Added(diff) if diff < chrono::Duration::zero() => writeln!(
+                f,
+                "  gap of {} was added to the session although the commit is older than its predecessor",
+                format_duration(diff)
+            )?,
+            Gap::Added(diff) => writeln!(
+                f,
+                "  gap of {} was added to the session since it does not exceed {} whole hours",
+                format_duration(diff),
+                self.max_gap
+            )?,
+            Gap::Broken(diff) => writeln!(
+                f,
+                "  gap of {} broke the session since it exceeds {} whole hours, a new session starts",
+                format_duration(diff),
+                self.max_gap
+            )?,
+            Gap::First => writeln!(f, "  first matching commit, the first session starts")?,
+        }
+
+        for loc in self.commit.locs() {
+            let verdict = match self.filter.rejecting_loc_option(loc) {
+                None => "counted".to_owned(),
+                Some(option) => format!("dropped by {}", option),
+            };
+
+            match (loc.added(), loc.removed()) {
+                (Some(added), Some(removed)) => writeln!(
+                    f,
+                    "  LOC {:+} (+{} -{}) of {} {}",
+                    loc.loc(),
+                    added,
+                    removed,
+                    loc.file(),
+                    verdict
+                )?,
+                _ => writeln!(f, "  LOC 0 (binary) of {} {}", loc.file(), verdict)?,
+            }
+        }
+        let loc = self.commit.loc_stats(self.filter);
+
+        writeln!(
+            f,
+            "  LOC {:+} (+{} -{}) overall",
+            loc.net(),
+            loc.added(),
+            loc.removed()
+        )
+    }
+}
@@ -0,0 +1,670 @@
+//! The aggregation of commits and working time by arbitrary dimensions.
+//!
+//! The commits of the detected sessions are turned into records, which are
+//! grouped by any combination of dimensions, such as the author and the
+//! month, before metrics are computed for every group. A record is a commit
+//! or, when grouping by a property of the changed files, a changed file.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use chrono::Datelike;
+
+use crate::{
+    attribution::Attribution,
+    authors::CanonicalNames,
+    loc::LocStats,
+    session::{hours, Session},
+    table::{Alignment, Table},
+    timezone::Timezone,
+    Commit, LocDiff,
+};
+
+/// The value of the file dimensions for commits without any matching files.
+pub const NO_FILES: &str = "(no files)";
+
+/// A property of a record to group by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Dimension {
+    /// The author's canonical name, see `CanonicalNames`.
+    Author,
+
+    /// The author's email address, in lower case.
+    Email,
+
+    /// The domain of the author's email address, in lower case.
+    Domain,
+
+    /// The commit hash.
+    Commit,
+
+    /// The first line of the commit message.
+    Subject,
+
+    /// The kind of change the commit message describes, see `Category`.
+    Category,
+
+    /// The type of a Conventional Commits header, e.g. `feat`.
+    Type,
+
+    /// The scope of a Conventional Commits header.
+    Scope,
+
+    /// The date of the commit.
+    Day,
+
+    /// The ISO week of the commit, e.g. `2022-W22`.
+    Week,
+
+    /// The month of the commit, e.g. `2022-06`.
+    Month,
+
+    /// The year of the commit.
+    Year,
+
+    /// The day of the week of the commit.
+    Weekday,
+
+    /// The hour of the day of the commit.
+    Hour,
+
+    /// The path of the changed file.
+    File,
+
+    /// The extension of the changed file.
+    Extension,
+
+    /// The top-level directory of the changed file, `.` for the root.
+    Directory,
+
+    /// The language of the changed file, see `crate::language`.
+    Language,
+
+    /// The name of the repository.
+    Repository,
+}
+
+impl Dimension {
+    /// Whether this dimension requires a record per changed file.
+    pub fn is_per_file(self) -> bool {
+        matches!(
+            self,
+            Self::File | Self::Extension | Self::Directory | Self::Language
+        )
+    }
+
+    /// The name of this dimension, as on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Author => "author",
+            Self::Email => "email",
+            Self::Domain => "domain",
+            Self::Commit => "commit",
+            Self::Subject => "subject",
+            Self::Category => "category",
+            Self::Type => "type",
+            Self::Scope => "scope",
+            Self::Day => "day",
+            Self::Week => "week",
+            Self::Month => "month",
+            Self::Year => "year",
+            Self::Weekday => "weekday",
+            Self::Hour => "hour",
+            Self::File => "file",
+            Self::Extension => "extension",
+            Self::Directory => "directory",
+            Self::Language => "language",
+            Self::Repository => "repository",
+        }
+    }
+
+    /// The dimension with the given name, ignoring the case.
+    ///
+    /// `date` is accepted as an alias for `day`.
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+
+        if name == "date" {
+            return Some(Self::Day);
+        }
+        <Self as clap::ValueEnum>::value_variants()
+            .iter()
+            .copied()
+            .find(|dimension| dimension.name() == name)
+    }
+
+    /// The key to sort the given record's value by.
+    ///
+    /// This is the value itself, except for weekdays, which are sorted from
+    /// Monday to Sunday.
+    pub fn sort_key(self, record: &Record, repository: &str) -> String {
+        match self {
+            Self::Weekday => record.date.weekday().num_days_from_monday().to_string(),
+            _ => self.value(record, repository),
+        }
+    }
+
+    /// The value of the given record.
+    pub fn value(self, record: &Record, repository: &str) -> String {
+        let path = || record.file.map(LocDiff::new_path).unwrap_or_default();
+
+        match self {
+            _ if self.is_per_file() && record.file.is_none() => NO_FILES.to_owned(),
+            Self::Author => record.author.clone(),
+            Self::Email => record.commit.author().email().to_lowercase(),
+            Self::Domain => record
+                .commit
+                .author()
+                .email()
+                .rsplit_once('@')
+                .map(|(_, domain)| domain.to_lowercase())
+                .unwrap_or_default(),
+            Self::Commit => record.commit.commit().to_owned(),
+            Self::Subject => record.commit.subject().to_owned(),
+            Self::Category => Category::classify(record.commit).to_string(),
+            Self::Type => record.commit.conventional().map_or_else(
+                || crate::conventional::NO_TYPE.to_owned(),
+                |header| header.kind().to_owned(),
+            ),
+            Self::Scope => record
+                .commit
+                .conventional()
+                .and_then(|header| header.scope())
+                .unwrap_or_default()
+                .to_owned(),
+            Self::Day => record.date.format("%Y-%m-%d").to_string(),
+            Self::Week => record.date.format("%G-W%V").to_string(),
+            Self::Month => record.date.format("%Y-%m").to_string(),
+            Self::Year => record.date.format("%Y").to_string(),
+            Self::Weekday => record.date.format("%a").to_string(),
+            Self::Hour => record.date.format("%H").to_string(),
+            Self::File => path(),
+            Self::Extension => std::path::Path::new(&path())
+                .extension()
+                .map(|extension| extension.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            Self::Directory => crate::hotspots::directory(&path(), 1),
+            Self::Language => record.language.clone().unwrap_or_default(),
+            Self::Repository => repository.to_owned(),
+        }
+    }
+}
+
+impl std::fmt::Display for Dimension {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// A number to compute for every group.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Metric {
+    /// The number of commits.
+    Commits,
+
+    /// The working time in hours.
+    Hours,
+
+    /// The number of inserted lines.
+    Added,
+
+    /// The number of deleted lines.
+    Removed,
+
+    /// The inserted minus the deleted lines.
+    Net,
+
+    /// The inserted plus the deleted lines.
+    Churn,
+
+    /// The number of distinct changed files.
+    Files,
+
+    /// The number of calendar days with at least one commit.
+    ActiveDays,
+}
+
+impl Metric {
+    /// Computes this metric over the given records.
+    pub fn compute(self, records: &[&Record]) -> f64 {
+        let loc = || records.iter().map(|record| record.loc).sum::<LocStats>();
+
+        match self {
+            Self::Commits => records
+                .iter()
+                .map(|record| record.commit.commit())
+                .collect::<BTreeSet<_>>()
+                .len() as f64,
+            Self::Hours => records.iter().map(|record| record.hours).sum(),
+            Self::Added => loc().added() as f64,
+            Self::Removed => loc().removed() as f64,
+            Self::Net => loc().net() as f64,
+            Self::Churn => loc().churn() as f64,
+            Self::Files => records
+                .iter()
+                .flat_map(|record| record.files.iter().map(|file| file.new_path()))
+                .collect::<BTreeSet<_>>()
+                .len() as f64,
+            Self::ActiveDays => records
+                .iter()
+                .map(|record| record.date.naive_local().date())
+                .collect::<BTreeSet<_>>()
+                .len() as f64,
+        }
+    }
+
+    /// Formats the given value of this metric, with two decimals for hours.
+    pub fn format(self, value: f64) -> String {
+        match self {
+            Self::Hours => format!("{:.2}", value),
+            _ => format!("{:.0}", value),
+        }
+    }
+
+    /// The column title of this metric.
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::Commits => "Commits",
+            Self::Hours => "Hours",
+            Self::Added => "Added",
+            Self::Removed => "Removed",
+            Self::Net => "Net",
+            Self::Churn => "Churn",
+            Self::Files => "Files",
+            Self::ActiveDays => "Active Days",
+        }
+    }
+}
+
+/// The kind of change a commit message describes.
+///
+/// Conventional prefixes such as `fix:` or `feat(parser):` are respected,
+/// otherwise the first word of the subject is used, e.g. `Add` or `Fixed`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Category {
+    /// New functionality.
+    Feature,
+
+    /// A bug fix.
+    Fix,
+
+    /// Documentation.
+    Docs,
+
+    /// Tests.
+    Test,
+
+    /// A restructuring without functional changes.
+    Refactor,
+
+    /// Maintenance, such as dependency updates and releases.
+    Chore,
+
+    /// A merge commit.
+    Merge,
+
+    /// Anything else.
+    Other,
+}
+
+impl Category {
+    /// Classifies the given commit by its message.
+    pub fn classify(commit: &Commit) -> Self {
+        let subject = commit.subject().trim().to_lowercase();
+
+        if commit.merge().is_some() || subject.starts_with("merge ") {
+            return Self::Merge;
+        }
+
+        let word = match commit.conventional() {
+            Some(header) => header.kind(),
+            None => subject
+                .split(|c: char| !c.is_alphanumeric())
+                .next()
+                .unwrap_or_default(),
+        };
+
+        match word {
+            "feat" | "feature" | "add" | "adds" | "added" | "implement" | "implemented"
+            | "introduce" | "support" => Self::Feature,
+            "fix" | "fixes" | "fixed" | "bugfix" | "hotfix" => Self::Fix,
+            "docs" | "doc" | "document" | "documented" | "readme" => Self::Docs,
+            "test" | "tests" => Self::Test,
+            "refactor" | "refactored" | "perf" | "style" | "clean" | "cleanup" | "rename"
+            | "move" | "simplify" => Self::Refactor,
+            "chore" | "build" | "ci" | "bump" | "release" | "upgrade" => Self::Chore,
+            _ => Self::Other,
+        }
+    }
+}
+
+impl std::fmt::Display for Category {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Feature => write!(f, "feature"),
+            Self::Fix => write!(f, "fix"),
+            Self::Docs => write!(f, "docs"),
+            Self::Test => write!(f, "test"),
+            Self::Refactor => write!(f, "refactor"),
+            Self::Chore => write!(f, "chore"),
+            Self::Merge => write!(f, "merge"),
+            Self::Other => write!(f, "other"),
+        }
+    }
+}
+
+/// A commit or a changed file to aggregate.
+#[derive(Clone, Debug)]
+pub struct Record<'a> {
+    /// The canonical name of the author, see `CanonicalNames`.
+    author: String,
+
+    /// The commit.
+    commit: &'a Commit,
+
+    /// The date of the commit in the requested time zone.
+    date: chrono::DateTime<chrono::FixedOffset>,
+
+    /// The changed file, if there is a record per file.
+    file: Option<&'a LocDiff>,
+
+    /// The changed files matching the filter.
+    files: Vec<&'a LocDiff>,
+
+    /// The language of the changed file, if there is a record per file.
+    language: Option<String>,
+
+    /// The working time in hours.
+    hours: f64,
+
+    /// The inserted and deleted lines.
+    loc: LocStats,
+}
+
+impl<'a> Record<'a> {
+    /// Creates a record per commit or per changed file of the given sessions.
+    ///
+    /// A record per commit is attributed the time since the previous commit
+    /// of its session, like the author summaries. With a record per file,
+    /// the working time is distributed over the commits and their files
+    /// matching the given filter as configured by `Attribution`, and commits
+    /// which were attributed working time without changing any matching
+    /// files keep a record without a file, such that the time is not lost.
+    /// The dates are converted into the given time zone.
+    pub fn collect(
+        sessions: &[Session<'a>],
+        filter: &crate::Filter,
+        timezone: Timezone,
+        per_file: bool,
+        attribution: Attribution,
+    ) -> Vec<Self> {
+        let names = CanonicalNames::new(sessions, filter);
+        let mut records = vec![];
+
+        for session in sessions {
+            let work = attribution.distribute(session, filter);
+            let intervals: HashMap<&str, f64> = session
+                .intervals()
+                .map(|interval| (interval.commit().commit(), hours(interval.duration())))
+                .collect();
+
+            for commit in session.commits() {
+                let (total, shares) = work.get(commit.commit()).cloned().unwrap_or_default();
+                let date = timezone.convert(commit.date());
+                let files: Vec<&LocDiff> = commit
+                    .locs()
+                    .iter()
+                    .filter(|loc| filter.check_loc(loc))
+                    .collect();
+
+                if per_file && (!files.is_empty() || total == 0.0) {
+                    for (file, hours) in files.iter().zip(shares) {
+                        records.push(Self {
+                            author: names.get(commit.author()).to_owned(),
+                            commit,
+                            date,
+                            file: Some(file),
+                            files: vec![file],
+                            language: Some(filter.language(file).to_owned()),
+                            hours,
+                            loc: file.loc_stats(),
+                        });
+                    }
+                } else {
+                    records.push(Self {
+                        author: names.get(commit.author()).to_owned(),
+                        commit,
+                        date,
+                        file: None,
+                        files,
+                        language: None,
+                        hours: if per_file {
+                            total
+                        } else {
+                            intervals.get(commit.commit()).copied().unwrap_or_default()
+                        },
+                        loc: commit.loc_stats(filter),
+                    });
+                }
+            }
+        }
+
+        records
+    }
+
+    /// The getter method for the field `commit` of the corresponding struct.
+    pub fn commit(&self) -> &'a Commit {
+        self.commit
+    }
+
+    /// The getter method for the field `date` of the corresponding struct.
+    pub fn date(&self) -> &chrono::DateTime<chrono::FixedOffset> {
+        &self.date
+    }
+
+    /// The getter method for the field `file` of the corresponding struct.
+    pub fn file(&self) -> Option<&'a LocDiff> {
+        self.file
+    }
+
+    /// The getter method for the field `files` of the corresponding struct.
+    pub fn files(&self) -> &[&'a LocDiff] {
+        &self.files
+    }
+
+    /// The getter method for the field `hours` of the corresponding struct.
+    pub fn hours(&self) -> f64 {
+        self.hours
+    }
+
+    /// The getter method for the field `loc` of the corresponding struct.
+    pub fn loc(&self) -> LocStats {
+        self.loc
+    }
+}
+
+/// Groups the given records by the values of the given dimensions.
+///
+/// The groups are sorted by their values, see `Dimension::sort_key`. Without
+/// any dimensions, all records form a single group.
+pub fn group<'r, 'a, I>(
+    records: I,
+    dimensions: &[Dimension],
+    repository: &str,
+) -> Vec<(Vec<String>, Vec<&'r Record<'a>>)>
+where
+    I: IntoIterator<Item = &'r Record<'a>>,
+{
+    let mut groups: BTreeMap<Vec<(String, String)>, Vec<&Record>> = BTreeMap::new();
+
+    for record in records {
+        let key = dimensions
+            .iter()
+            .map(|d| (d.sort_key(record, repository), d.value(record, repository)))
+            .collect();
+
+        groups.entry(key).or_default().push(record);
+    }
+    if dimensions.is_empty() && groups.is_empty() {
+        groups.insert(vec![], vec![]);
+    }
+
+    groups
+        .into_iter()
+        .map(|(key, records)| (key.into_iter().map(|(_, value)| value).collect(), records))
+        .collect()
+}
+
+/// The metrics of a single group.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Group {
+    /// The values of the dimensions.
+    key: Vec<String>,
+
+    /// The values of the metrics.
+    values: Vec<f64>,
+}
+
+impl Group {
+    /// The getter method for the field `key` of the corresponding struct.
+    pub fn key(&self) -> &[String] {
+        &self.key
+    }
+
+    /// The getter method for the field `values` of the corresponding struct.
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+}
+
+/// The metrics of every combination of the values of some dimensions.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Grouping {
+    /// The dimensions to group by.
+    dimensions: Vec<Dimension>,
+
+    /// The metrics to compute.
+    metrics: Vec<Metric>,
+
+    /// The groups, sorted by their values.
+    groups: Vec<Group>,
+}
+
+impl Grouping {
+    /// Groups the commits of the given sessions by the given dimensions and
+    /// computes the given metrics for every group.
+    ///
+    /// See `Record::collect` for how the working time is attributed.
+    pub fn new(
+        dimensions: &[Dimension],
+        metrics: &[Metric],
+        sessions: &[Session],
+        filter: &crate::Filter,
+        timezone: Timezone,
+        attribution: Attribution,
+        repository: &str,
+    ) -> Self {
+        let per_file = dimensions.iter().any(|dimension| dimension.is_per_file());
+        let records = Record::collect(sessions, filter, timezone, per_file, attribution);
+
+        Self {
+            dimensions: dimensions.to_vec(),
+            metrics: metrics.to_vec(),
+            groups: group(&records, dimensions, repository)
+                .into_iter()
+                .map(|(key, records)| Group {
+                    key,
+                    values: metrics
+                        .iter()
+                        .map(|metric| metric.compute(&records))
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    /// The getter method for the field `dimensions` of the corresponding
+    /// struct.
+    pub fn dimensions(&self) -> &[Dimension] {
+        &self.dimensions
+    }
+
+    /// The getter method for the field `groups` of the corresponding struct.
+    pub fn groups(&self) -> &[Group] {
+        &self.groups
+    }
+
+    /// Sorts the groups by the given metric, descending.
+    ///
+    /// Ties keep their order. Nothing happens if the metric is not computed.
+    pub fn sort_by(&mut self, metric: Metric) {
+        if let Some(index) = self.metrics.iter().position(|m| *m == metric) {
+            self.groups
+                .sort_by(|a, b| b.values[index].total_cmp(&a.values[index]));
+        }
+    }
+
+    /// The column titles, the dimensions followed by the metrics.
+    pub fn header(&self) -> Vec<String> {
+        self.dimensions
+            .iter()
+            .map(|dimension| {
+                let name = dimension.name();
+
+                name[..1].to_uppercase() + &name[1..]
+            })
+            .chain(self.metrics.iter().map(|metric| metric.title().to_owned()))
+            .collect()
+    }
+
+    /// The getter method for the field `metrics` of the corresponding struct.
+    pub fn metrics(&self) -> &[Metric] {
+        &self.metrics
+    }
+
+    /// The formatted cells of every group, see `Grouping::header`.
+    pub fn rows(&self) -> Vec<Vec<String>> {
+        self.groups
+            .iter()
+            .map(|group| {
+                group
+                    .key
+                    .iter()
+                    .cloned()
+                    .chain(
+                        self.metrics
+                            .iter()
+                            .zip(&group.values)
+                            .map(|(metric, value)| metric.format(*value)),
+                    )
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Converts the groups into a table, aligning the metrics to the right.
+    pub fn to_table(&self) -> Table {
+        let header = self.header();
+        let columns: Vec<(&str, Alignment)> = header
+            .iter()
+            .enumerate()
+            .map(|(index, title)| {
+                let alignment = if index < self.dimensions.len() {
+                    Alignment::Left
+                } else {
+                    Alignment::Right
+                };
+
+                (title.as_str(), alignment)
+            })
+            .collect();
+        let mut table = Table::new(&columns);
+
+        for row in self.rows() {
+            table.push(row);
+        }
+
+        table
+    }
+}
@@ -0,0 +1,406 @@
+//! The change history of individual files and directories.
+//!
+//! Files which are changed often, by many authors or in large amounts are
+//! hotspots and candidates for refactoring. Renamed files keep their history,
+//! which is attributed to their latest path.
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{
+    authors::CanonicalNames,
+    loc::LocStats,
+    session::Session,
+    table::{Alignment, Table},
+    timezone::Timezone,
+    Commit, LocDiff,
+};
+
+/// The aggregates of a single file or directory.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Hotspot {
+    /// The path of the file or directory.
+    path: String,
+
+    /// The number of commits changing the file or directory.
+    commits: usize,
+
+    /// The inserted and deleted lines.
+    loc: LocStats,
+
+    /// The number of distinct canonical authors, see `CanonicalNames`.
+    authors: usize,
+
+    /// The date of the last commit changing the file or directory.
+    last_modified: chrono::DateTime<chrono::FixedOffset>,
+}
+
+impl Hotspot {
+    /// The getter method for the field `authors` of the corresponding struct.
+    pub fn authors(&self) -> usize {
+        self.authors
+    }
+
+    /// The getter method for the field `commits` of the corresponding struct.
+    pub fn commits(&self) -> usize {
+        self.commits
+    }
+
+    /// The getter method for the field `last_modified` of the corresponding
+    /// struct.
+    pub fn last_modified(&self) -> &chrono::DateTime<chrono::FixedOffset> {
+        &self.last_modified
+    }
+
+    /// The getter method for the field `loc` of the corresponding struct.
+    pub fn loc(&self) -> LocStats {
+        self.loc
+    }
+
+    /// The getter method for the field `path` of the corresponding struct.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+/// The hotspots among the files and directories.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Hotspots {
+    /// The changed files.
+    files: Vec<Hotspot>,
+
+    /// The directories of the changed files, up to a certain depth.
+    directories: Vec<Hotspot>,
+}
+
+impl Hotspots {
+    /// Aggregates the changes of the files matching the given filter.
+    ///
+    /// Directories are cut after the given number of components, files in
+    /// the root directory belong to `.`. The dates are converted into the
+    /// given time zone. Both lists are sorted by the given key.
+    pub fn new(
+        sessions: &[Session],
+        filter: &crate::Filter,
+        timezone: Timezone,
+        depth: usize,
+        sort_by: SortKey,
+    ) -> Self {
+        let commits: Vec<&Commit> = sessions
+            .iter()
+            .flat_map(|session| session.commits().iter().copied())
+            .collect();
+        let renames = Renames::new(commits.iter().rev().copied());
+        let names = CanonicalNames::new(sessions, filter);
+        let mut files: HashMap<String, Builder> = HashMap::new();
+        let mut directories: HashMap<String, Builder> = HashMap::new();
+
+        for commit in &commits {
+            let date = timezone.convert(commit.date());
+
+            for loc in commit.locs().iter().filter(|loc| filter.check_loc(loc)) {
+                let path = renames.resolve(loc);
+                let directory = directory(&path, depth);
+
+                for (map, key) in [(&mut files, path), (&mut directories, directory)] {
+                    let builder = map.entry(key).or_insert_with(|| Builder::new(date));
+
+                    builder.authors.insert(names.get(commit.author()));
+                    builder.commits.insert(commit.commit());
+                    builder.last_modified = builder.last_modified.max(date);
+                    builder.loc += loc.loc_stats();
+                }
+            }
+        }
+
+        let build = |map: HashMap<String, Builder>| {
+            let mut hotspots: Vec<Hotspot> = map
+                .into_iter()
+                .map(|(path, builder)| builder.build(path))
+                .collect();
+
+            sort_by.sort(&mut hotspots);
+            hotspots
+        };
+
+        Self {
+            files: build(files),
+            directories: build(directories),
+        }
+    }
+
+    /// The getter method for the field `directories` of the corresponding
+    /// struct.
+    pub fn directories(&self) -> &[Hotspot] {
+        &self.directories
+    }
+
+    /// The getter method for the field `files` of the corresponding struct.
+    pub fn files(&self) -> &[Hotspot] {
+        &self.files
+    }
+
+    /// Converts the files and directories into a table each.
+    pub fn to_tables(&self) -> [Table; 2] {
+        [("File", &self.files), ("Directory", &self.directories)].map(|(path, hotspots)| {
+            let mut table = Table::new(&[
+                (path, Alignment::Left),
+                ("Commits", Alignment::Right),
+                ("Added", Alignment::Right),
+                ("Removed", Alignment::Right),
+                ("Churn", Alignment::Right),
+                ("Authors", Alignment::Right),
+                ("Last Modified", Alignment::Left),
+            ]);
+
+            for hotspot in hotspots {
+                table.push(vec![
+                    hotspot.path().to_owned(),
+                    hotspot.commits().to_string(),
+                    hotspot.loc().added().to_string(),
+                    hotspot.loc().removed().to_string(),
+                    hotspot.loc().churn().to_string(),
+                    hotspot.authors().to_string(),
+                    hotspot.last_modified().format("%Y-%m-%d %H:%M").to_string(),
+                ]);
+            }
+
+            table
+        })
+    }
+
+    /// Keeps the given number of files and directories.
+    pub fn truncate(&mut self, count: usize) {
+        self.files.truncate(count);
+        self.directories.truncate(count);
+    }
+}
+
+/// The columns to sort the hotspots by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum SortKey {
+    /// The number of commits.
+    Commits,
+
+    /// The number of inserted lines.
+    Added,
+
+    /// The number of deleted lines.
+    Removed,
+
+    /// The number of inserted and deleted lines.
+    Churn,
+
+    /// The number of distinct authors.
+    Authors,
+
+    /// The date of the last change.
+    LastModified,
+}
+
+impl SortKey {
+    /// Sorts the given hotspots by this key, descending.
+    ///
+    /// Ties are sorted by path.
+    pub fn sort(self, hotspots: &mut [Hotspot]) {
+        hotspots.sort_by(|a, b| {
+            let ordering = match self {
+                Self::Commits => b.commits.cmp(&a.commits),
+                Self::Added => b.loc.added().cmp(&a.loc.added()),
+                Self::Removed => b.loc.removed().cmp(&a.loc.removed()),
+                Self::Churn => b.loc.churn().cmp(&a.loc.churn()),
+                Self::Authors => b.authors.cmp(&a.authors),
+                Self::LastModified => b.last_modified.cmp(&a.last_modified),
+            };
+
+            ordering.then_with(|| a.path.cmp(&b.path))
+        });
+    }
+}
+
+/// The latest paths of the changed files.
+#[derive(Debug, Default)]
+pub struct Renames {
+    /// The latest path of every change, identified by its address.
+    paths: HashMap<*const LocDiff, String>,
+}
+
+impl Renames {
+    /// Resolves the changes of the given commits, newest first.
+    ///
+    /// A rename only applies to the commits older than the one it was
+    /// recorded in. Hence, a new file created at the old path of a renamed
+    /// file keeps its own history.
+    pub fn new<'a, I>(commits: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Commit>,
+    {
+        let mut renames = Self::default();
+        let mut latest: HashMap<String, String> = HashMap::new();
+
+        for commit in commits {
+            for loc in commit.locs() {
+                let path = loc.new_path();
+                let path = latest.get(&path).cloned().unwrap_or(path);
+
+                renames.paths.insert(loc, path);
+            }
+
+            for loc in commit.locs() {
+                let (old, new) = (loc.old_path(), loc.new_path());
+
+                if old != new {
+                    let path = latest.get(&new).cloned().unwrap_or(new);
+                    latest.insert(old, path);
+                }
+            }
+        }
+
+        renames
+    }
+
+    /// The latest path of the file changed by the given change.
+    ///
+    /// Changes of commits which were not passed to `Renames::new` keep their
+    /// path.
+    pub fn resolve(&self, loc: &LocDiff) -> String {
+        self.paths
+            .get(&(loc as *const LocDiff))
+            .cloned()
+            .unwrap_or_else(|| loc.new_path())
+    }
+}
+
+/// The directory of the given file, cut after the given number of components.
+///
+/// Files in the root directory belong to `.`.
+pub fn directory(path: &str, depth: usize) -> String {
+    let components: Vec<&str> = path.split('/').collect();
+    let directories = &components[..components.len() - 1];
+
+    if directories.is_empty() || depth == 0 {
+        ".".to_owned()
+    } else {
+        directories[..depth.min(directories.len())].join("/")
+    }
+}
+
+/// The partial aggregates of a file or directory.
+struct Builder<'a> {
+    /// The names of the authors.
+    authors: BTreeSet<&'a str>,
+
+    /// The hashes of the commits.
+    commits: BTreeSet<&'a str>,
+
+    /// The date of the last change.
+    last_modified: chrono::DateTime<chrono::FixedOffset>,
+
+    /// The inserted and deleted lines.
+    loc: LocStats,
+}
+
+impl<'a> Builder<'a> {
+    /// Creates an empty builder for a path changed at the given date.
+    fn new(date: chrono::DateTime<chrono::FixedOffset>) -> Self {
+        Self {
+            authors: BTreeSet::new(),
+            commits: BTreeSet::new(),
+            last_modified: date,
+            loc: LocStats::default(),
+        }
+    }
+
+    /// Finishes the hotspot of the given path.
+    fn build(self, path: String) -> Hotspot {
+        Hotspot {
+            path,
+            commits: self.commits.len(),
+            loc: self.loc,
+            authors: self.authors.len(),
+            last_modified: self.last_modified,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Renames;
+
+    /// A file which is renamed while a new file takes its old path.
+    const LOG: &str = "commit 3333333333333333333333333333333333333333
+Author: Alice <alice@example.com>
+Date:   Wed Jun 1 11:00:00 2022 +0000
+
+    Edit the new library
+
+3\t0\tsrc/lib.rs
+
+commit 2222222222222222222222222222222222222222
+Author: Alice <alice@example.com>
+Date:   Wed Jun 1 10:00:00 2022 +0000
+
+    Move the library aside
+
+0\t0\tsrc/{lib.rs => old.rs}
+2\t0\tsrc/lib.rs
+
+commit 1111111111111111111111111111111111111111
+Author: Alice <alice@example.com>
+Date:   Wed Jun 1 09:00:00 2022 +0000
+
+    Add the library
+
+1\t0\tsrc/lib.rs
+";
+
+    #[test]
+    fn renames_only_apply_to_older_commits() {
+        let (commits, _) = crate::Commit::parse_log(LOG);
+        let renames = Renames::new(&commits);
+        let paths: Vec<Vec<String>> = commits
+            .iter()
+            .map(|commit| commit.locs().iter().map(|l| renames.resolve(l)).collect())
+            .collect();
+
+        assert_eq!(
+            paths,
+            [
+                vec!["src/lib.rs"],
+                vec!["src/old.rs", "src/lib.rs"],
+                vec!["src/old.rs"],
+            ]
+        );
+    }
+
+    #[test]
+    fn authors_are_counted_once_per_canonical_author() {
+        let (commits, _) = crate::Commit::parse_log(
+            "commit 2222222222222222222222222222222222222222
+Author: alice <ALICE@example.com>
+Date:   Wed Jun 1 10:00:00 2022 +0000
+
+    Second commit
+
+1\t0\tREADME.md
+
+commit 1111111111111111111111111111111111111111
+Author: Alice <alice@example.com>
+Date:   Wed Jun 1 09:00:00 2022 +0000
+
+    First commit
+
+1\t0\tREADME.md
+",
+        );
+        let sessions = crate::session::Session::detect(commits.iter().rev(), 3);
+        let hotspots = super::Hotspots::new(
+            &sessions,
+            &crate::Filter::default(),
+            crate::timezone::Timezone::Utc,
+            1,
+            super::SortKey::Commits,
+        );
+
+        assert_eq!(hotspots.files()[0].authors(), 1);
+    }
+}
@@ -0,0 +1,154 @@
+//! The export of work sessions in iCalendar format.
+//!
+//! Every session becomes an event such that the estimated working time can be
+//! compared to meetings and other appointments in a calendar app. The format
+//! follows RFC 5545: lines end with CRLF, are folded after 75 octets and all
+//! times are given in UTC.
+
+use crate::session::Session;
+
+/// The maximum length of a line in octets, excluding the line break.
+const LINE_LENGTH: usize = 75;
+
+/// The minimum duration of an event in minutes.
+///
+/// A session consisting of a single commit has no duration, and a session
+/// whose commits are out of order may even end before it starts. Calendars
+/// reject or hide such events, hence they last at least this long.
+const MIN_DURATION: i64 = 30;
+
+/// Renders an iCalendar document with an event per session.
+///
+/// The summary of an event names the repository and the authors, the
+/// description lists the subjects of all commits. The events are identified by
+/// their first commit such that importing a document again updates them. See
+/// `MIN_DURATION` for the shortest event.
+pub fn calendar(sessions: &[Session], repository: &str) -> String {
+    let stamp = utc(&chrono::Utc::now());
+    let mut calendar = String::new();
+
+    push(&mut calendar, "BEGIN:VCALENDAR");
+    push(&mut calendar, "VERSION:2.0");
+    push(
+        &mut calendar,
+        &format!(
+            "PRODID:-//commit-analyzer//{}//EN",
+            env!("CARGO_PKG_VERSION")
+        ),
+    );
+    push(&mut calendar, "CALSCALE:GREGORIAN");
+
+    for session in sessions {
+        let subjects: Vec<String> = session
+            .commits()
+            .iter()
+            .map(|commit| format!("- {}", commit.subject()))
+            .collect();
+
+        let end = (*session.end()).max(*session.start() + chrono::Duration::minutes(MIN_DURATION));
+
+        push(&mut calendar, "BEGIN:VEVENT");
+        push(
+            &mut calendar,
+            &format!("UID:{}@commit-analyzer", session.commits()[0].commit()),
+        );
+        push(&mut calendar, &format!("DTSTAMP:{}", stamp));
+        push(&mut calendar, &format!("DTSTART:{}", utc(session.start())));
+        push(&mut calendar, &format!("DTEND:{}", utc(&end)));
+        push(
+            &mut calendar,
+            &format!(
+                "SUMMARY:{}",
+                escape(&format!("{}: {}", repository, session.authors().join(", ")))
+            ),
+        );
+        push(
+            &mut calendar,
+            &format!("DESCRIPTION:{}", escape(&subjects.join("\n"))),
+        );
+        push(&mut calendar, "END:VEVENT");
+    }
+
+    push(&mut calendar, "END:VCALENDAR");
+    calendar
+}
+
+/// Escapes the characters with a special meaning in text values.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Appends a content line, folding it after every 75 octets.
+fn push(calendar: &mut String, line: &str) {
+    let mut length = 0;
+
+    for character in line.chars() {
+        if length + character.len_utf8() > LINE_LENGTH {
+            calendar.push_str("\r\n ");
+            length = 1;
+        }
+        calendar.push(character);
+        length += character.len_utf8();
+    }
+    calendar.push_str("\r\n");
+}
+
+/// Formats the given date as a UTC date-time.
+fn utc<Tz: chrono::TimeZone>(date: &chrono::DateTime<Tz>) -> String {
+    date.with_timezone(&chrono::Utc)
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::session::Session;
+
+    /// Two commits by different authors, the later one dated before the
+    /// earlier one.
+    const LOG: &str = "commit 2222222222222222222222222222222222222222
+Author: Bob <bob@example.com>
+Date:   Wed Jun 1 08:50:00 2022 +0000
+
+    Later commit with an earlier date
+
+commit 1111111111111111111111111111111111111111
+Author: Alice <alice@example.com>
+Date:   Wed Jun 1 09:00:00 2022 +0000
+
+    First commit
+";
+
+    fn events(sessions: &[Session]) -> Vec<(String, String)> {
+        let calendar = super::calendar(sessions, "repo");
+        let field = |name: &str| {
+            calendar
+                .lines()
+                .filter_map(|line| line.strip_prefix(name))
+                .map(str::to_owned)
+                .collect::<Vec<_>>()
+        };
+
+        field("DTSTART:").into_iter().zip(field("DTEND:")).collect()
+    }
+
+    #[test]
+    fn events_last_at_least_the_minimum_duration() {
+        let (commits, _) = crate::Commit::parse_log(LOG);
+
+        let single = Session::detect(commits.iter().rev().take(1), 3);
+        assert_eq!(
+            events(&single),
+            [("20220601T090000Z".to_owned(), "20220601T093000Z".to_owned())]
+        );
+
+        let reversed = Session::detect(commits.iter().rev(), 3);
+        assert_eq!(
+            events(&reversed),
+            [("20220601T090000Z".to_owned(), "20220601T093000Z".to_owned())]
+        );
+    }
+}
@@ -0,0 +1,260 @@
+//! The classification of files by their programming language.
+//!
+//! Files are classified by their name or extension, similar to GitHub's
+//! Linguist. The built-in table can be overridden on the command line with
+//! patterns like `*.tpl=HTML`, `Jenkinsfile=Groovy` or `vendor/=Vendored`.
+//! Files which are not known belong to `Other`.
+
+use crate::{
+    attribution::Attribution,
+    group::{Dimension, Grouping, Metric},
+    session::Session,
+    timezone::Timezone,
+};
+
+/// The language of files which are not known.
+pub const OTHER: &str = "Other";
+
+/// The languages of well-known file names.
+const FILENAMES: &[(&str, &str)] = &[
+    ("BUILD", "Starlark"),
+    ("CMakeLists.txt", "CMake"),
+    ("Cargo.lock", "TOML"),
+    ("Dockerfile", "Dockerfile"),
+    ("GNUmakefile", "Makefile"),
+    ("Gemfile", "Ruby"),
+    ("Jenkinsfile", "Groovy"),
+    ("Makefile", "Makefile"),
+    ("Rakefile", "Ruby"),
+    ("Vagrantfile", "Ruby"),
+    ("makefile", "Makefile"),
+];
+
+/// The languages of well-known file extensions, in lower case.
+const EXTENSIONS: &[(&str, &str)] = &[
+    ("adoc", "AsciiDoc"),
+    ("bash", "Shell"),
+    ("bat", "Batchfile"),
+    ("c", "C"),
+    ("cc", "C++"),
+    ("clj", "Clojure"),
+    ("cmake", "CMake"),
+    ("cmd", "Batchfile"),
+    ("cpp", "C++"),
+    ("cs", "C#"),
+    ("css", "CSS"),
+    ("cxx", "C++"),
+    ("dart", "Dart"),
+    ("erl", "Erlang"),
+    ("ex", "Elixir"),
+    ("exs", "Elixir"),
+    ("fs", "F#"),
+    ("go", "Go"),
+    ("gradle", "Groovy"),
+    ("graphql", "GraphQL"),
+    ("groovy", "Groovy"),
+    ("h", "C"),
+    ("hcl", "HCL"),
+    ("hh", "C++"),
+    ("hpp", "C++"),
+    ("hs", "Haskell"),
+    ("htm", "HTML"),
+    ("html", "HTML"),
+    ("ini", "INI"),
+    ("ipynb", "Jupyter Notebook"),
+    ("java", "Java"),
+    ("jl", "Julia"),
+    ("js", "JavaScript"),
+    ("json", "JSON"),
+    ("jsx", "JavaScript"),
+    ("kt", "Kotlin"),
+    ("kts", "Kotlin"),
+    ("less", "Less"),
+    ("lua", "Lua"),
+    ("m", "Objective-C"),
+    ("markdown", "Markdown"),
+    ("md", "Markdown"),
+    ("mjs", "JavaScript"),
+    ("ml", "OCaml"),
+    ("mm", "Objective-C++"),
+    ("nix", "Nix"),
+    ("php", "PHP"),
+    ("pl", "Perl"),
+    ("pm", "Perl"),
+    ("proto", "Protocol Buffer"),
+    ("ps1", "PowerShell"),
+    ("py", "Python"),
+    ("r", "R"),
+    ("rb", "Ruby"),
+    ("rs", "Rust"),
+    ("rst", "reStructuredText"),
+    ("sass", "Sass"),
+    ("scala", "Scala"),
+    ("scss", "SCSS"),
+    ("sh", "Shell"),
+    ("sql", "SQL"),
+    ("svelte", "Svelte"),
+    ("svg", "SVG"),
+    ("swift", "Swift"),
+    ("tex", "TeX"),
+    ("tf", "HCL"),
+    ("toml", "TOML"),
+    ("ts", "TypeScript"),
+    ("tsx", "TSX"),
+    ("txt", "Text"),
+    ("vue", "Vue"),
+    ("xml", "XML"),
+    ("yaml", "YAML"),
+    ("yml", "YAML"),
+    ("zig", "Zig"),
+    ("zsh", "Shell"),
+];
+
+/// A user-defined language of the files matching a pattern.
+///
+/// A valid override consists of a pattern and a language, separated by `=`.
+/// The pattern is either
+///
+/// * `*.` followed by an extension, which is compared ignoring the case,
+/// * a directory ending with `/`, which matches all files below it, or
+/// * a file name, which matches files of this name in any directory.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Override {
+    /// The pattern of the paths.
+    pattern: String,
+
+    /// The language of the matching files.
+    language: String,
+}
+
+impl Override {
+    /// Whether the given path matches the pattern.
+    fn matches(&self, path: &str) -> bool {
+        if let Some(extension) = self.pattern.strip_prefix("*.") {
+            extension_of(path).is_some_and(|e| e.eq_ignore_ascii_case(extension))
+        } else if self.pattern.ends_with('/') {
+            path.starts_with(&self.pattern) || path.contains(&format!("/{}", self.pattern))
+        } else {
+            file_name(path) == self.pattern
+        }
+    }
+}
+
+impl std::str::FromStr for Override {
+    type Err = OverrideParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (pattern, language) = text
+            .split_once('=')
+            .ok_or(OverrideParseError::SeparatorMissing)?;
+        let (pattern, language) = (pattern.trim(), language.trim());
+
+        if pattern.is_empty() || pattern == "*." {
+            Err(OverrideParseError::PatternMissing)
+        } else if language.is_empty() {
+            Err(OverrideParseError::LanguageMissing)
+        } else {
+            Ok(Self {
+                pattern: pattern.to_owned(),
+                language: language.to_owned(),
+            })
+        }
+    }
+}
+
+/// The set of errors which may occur.
+#[derive(Debug)]
+pub enum OverrideParseError {
+    /// The language after the `=` is empty.
+    LanguageMissing,
+
+    /// The pattern before the `=` is empty.
+    PatternMissing,
+
+    /// The pattern and the language are not separated by a `=`.
+    SeparatorMissing,
+}
+
+impl std::fmt::Display for OverrideParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LanguageMissing => write!(f, "the language is missing"),
+            Self::PatternMissing => write!(f, "the pattern is missing"),
+            Self::SeparatorMissing => {
+                write!(f, "expected a pattern and a language, e.g. *.tpl=HTML")
+            }
+        }
+    }
+}
+
+impl std::error::Error for OverrideParseError {}
+
+/// The language of the file at the given path.
+///
+/// The first matching override wins. Otherwise, the file name and then the
+/// extension are looked up in the built-in tables.
+pub fn classify<'a>(path: &str, overrides: &'a [Override]) -> &'a str {
+    if let Some(language) = overrides.iter().find(|o| o.matches(path)) {
+        return &language.language;
+    }
+
+    let name = file_name(path);
+    if let Some((_, language)) = FILENAMES.iter().find(|(known, _)| *known == name) {
+        return language;
+    }
+
+    extension_of(path)
+        .map(str::to_lowercase)
+        .and_then(|extension| {
+            EXTENSIONS
+                .iter()
+                .find(|(known, _)| *known == extension)
+                .map(|(_, language)| *language)
+        })
+        .unwrap_or(OTHER)
+}
+
+/// Aggregates the changed files, commits, LOC and working time per language.
+///
+/// See `Attribution` for how the working time is attributed. The
+/// languages are sorted by their churn, descending.
+pub fn breakdown(
+    sessions: &[Session],
+    filter: &crate::Filter,
+    timezone: Timezone,
+    attribution: Attribution,
+    repository: &str,
+) -> Grouping {
+    let mut grouping = Grouping::new(
+        &[Dimension::Language],
+        &[
+            Metric::Files,
+            Metric::Commits,
+            Metric::Added,
+            Metric::Removed,
+            Metric::Churn,
+            Metric::Hours,
+        ],
+        sessions,
+        filter,
+        timezone,
+        attribution,
+        repository,
+    );
+
+    grouping.sort_by(Metric::Churn);
+    grouping
+}
+
+/// The extension of the file at the given path, if any.
+fn extension_of(path: &str) -> Option<&str> {
+    file_name(path)
+        .rsplit_once('.')
+        .map(|(_, extension)| extension)
+        .filter(|extension| !extension.is_empty())
+}
+
+/// The last component of the given path.
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
@@ -16,25 +16,87 @@
 //! 1. [Description](#description)
 //! 1. [Configured Options and Subcommands](#configured-options-and-subcommands)
 //!    1. [Options](#options)
+//!       1. [`--author-columns`](#--author-columns)
 //!       1. [`--author-contains`, `-a`](#--author-contains--a)
 //!       1. [`--author-equals`](#--author-equals)
+//!       1. [`--authors`](#--authors)
+//!       1. [`--chart`](#--chart)
+//!       1. [`--columns`](#--columns)
 //!       1. [`--commit-contains`, `-c`](#--commit-contains--c)
 //!       1. [`--commit-equals`](#--commit-equals)
+//!       1. [`--coupling`](#--coupling)
+//!       1. [`--coupling-depth`](#--coupling-depth)
+//!       1. [`--coupling-dot`](#--coupling-dot)
+//!       1. [`--coupling-max-files`](#--coupling-max-files)
+//!       1. [`--coupling-min-commits`](#--coupling-min-commits)
+//!       1. [`--coupling-min-confidence`](#--coupling-min-confidence)
+//!       1. [`--delimiter`](#--delimiter)
 //!       1. [`--duration`, `-d`](#--duration--d)
 //!       1. [`--email-contains`, `-e`](#--email-contains--e)
 //!       1. [`--email-equals`](#--email-equals)
+//!       1. [`--explain`, `-x`](#--explain--x)
 //!       1. [`--file-extension`, `-f`](#--file-extension--f)
+//!       1. [`--format`](#--format)
+//!       1. [`--group-by`](#--group-by)
+//!       1. [`--group-by-period`, `-g`](#--group-by-period--g)
+//!       1. [`--group-metrics`](#--group-metrics)
 //!       1. [`--help`, `-h`](#--help--h)
+//!       1. [`--hotspot-depth`](#--hotspot-depth)
+//!       1. [`--hotspot-sort-by`](#--hotspot-sort-by)
+//!       1. [`--hotspots`](#--hotspots)
+//!       1. [`--hotspots-output`](#--hotspots-output)
+//!       1. [`--ics-output`](#--ics-output)
+//!       1. [`--language`](#--language)
+//!       1. [`--language-override`](#--language-override)
+//!       1. [`--languages`](#--languages)
+//!       1. [`--loc-metric`](#--loc-metric)
+//!       1. [`--merge-author-names`](#--merge-author-names)
 //!       1. [`--message-starts-with`, `-l`](#--message-starts-with--l)
 //!       1. [`--message-contains`, `-m`](#--message-contains--m)
 //!       1. [`--message-equals`](#--message-equals)
 //!       1. [`--output`, `-o`](#--output--o)
+//!       1. [`--ownership`](#--ownership)
+//!       1. [`--ownership-as-of`](#--ownership-as-of)
+//!       1. [`--ownership-depth`](#--ownership-depth)
+//!       1. [`--ownership-half-life`](#--ownership-half-life)
+//!       1. [`--ownership-stale-after`](#--ownership-stale-after)
+//!       1. [`--ownership-weight`](#--ownership-weight)
+//!       1. [`--per-commit`](#--per-commit)
+//!       1. [`--punch-card`](#--punch-card)
+//!       1. [`--punch-card-output`](#--punch-card-output)
+//!       1. [`--repository`](#--repository)
+//!       1. [`--scope`](#--scope)
+//!       1. [`--sessions`, `-s`](#--sessions--s)
+//!       1. [`--sessions-output`](#--sessions-output)
+//!       1. [`--sort-by`](#--sort-by)
+//!       1. [`--sqlite-output`](#--sqlite-output)
+//!       1. [`--sprint-calendar`](#--sprint-calendar)
+//!       1. [`--svg-chart`](#--svg-chart)
+//!       1. [`--svg-output`](#--svg-output)
+//!       1. [`--svg-size`](#--svg-size)
+//!       1. [`--ticket-pattern`](#--ticket-pattern)
+//!       1. [`--tickets`](#--tickets)
+//!       1. [`--tickets-output`](#--tickets-output)
+//!       1. [`--time-attribution`](#--time-attribution)
+//!       1. [`--time-by`](#--time-by)
+//!       1. [`--time-depth`](#--time-depth)
+//!       1. [`--timezone`, `-z`](#--timezone--z)
+//!       1. [`--top`](#--top)
+//!       1. [`--tracker`](#--tracker)
+//!       1. [`--tracker-description`](#--tracker-description)
+//!       1. [`--tracker-output`](#--tracker-output)
+//!       1. [`--tracker-project`](#--tracker-project)
+//!       1. [`--tracker-tags`](#--tracker-tags)
+//!       1. [`--type`](#--type)
+//!       1. [`--types`](#--types)
 //!       1. [`--verbose`, `-v`](#--verbose--v)
+//!       1. [`--week-start`](#--week-start)
 //!       1. [`--version`, `-V`](#--version--v)
 //!    1. [Subcommands](#subcommands)
 //!       1. [`git-history`](#git-history)
 //!       1. [`help`](#help-1)
 //!       1. [`log-file`](#log-file)
+//!       1. [`query`](#query)
 //!       1. [`stdin`](#stdin)
 //! 1. [Installation and Updation](#installation-and-updating)
 //!
@@ -53,7 +115,10 @@
 //!
 //! You can also give `commit-analyzer` an output file such that it will
 //! generate a CSV with the date, the number of commits made on the respective
-//! days and the LOC changes due to them.
+//! days, the LOC changes due to them and the hours worked. Working time which
+//! spans midnight is split between both days. The CSV files follow RFC 4180,
+//! their columns and delimiter can be configured and they can list every commit
+//! on its own row, too.
 //!
 //! ```bash
 //! commit-analyzer --git --output commits-and-loc-by-date.csv
@@ -64,6 +129,10 @@
 //!
 //! ### Options
 //!
+//! #### `--author-columns`
+//!
+//! Adds a column with the hours of every author to the output file.
+//!
 //! #### `--author-contains`, `-a`
 //!
 //! Filters for certain author names. ORs if specified multiple times.
@@ -72,6 +141,44 @@
 //!
 //! Filters for certain author names. ORs if specified multiple times.
 //!
+//! #### `--authors`
+//!
+//! Lists every author with the estimated hours and their share of the total,
+//! the number of sessions, commits and active days, the inserted and deleted
+//! lines and the dates of the first and last commit. Identities sharing an
+//! email address, ignoring its case, are considered the same author, see
+//! `--merge-author-names` to also merge those sharing a name. To map several
+//! addresses to one author, write the log with `git log --use-mailmap` and a
+//! `.mailmap` file. The time between two commits of a session is attributed to
+//! the author of the later one.
+//!
+//! #### `--chart`
+//!
+//! Draws a chart of the estimated hours in the terminal, may be specified
+//! multiple times. This is one of:
+//!
+//! * `sparkline` draws a single line with a character per day.
+//! * `bars` draws a horizontal bar per period, see `--group-by-period`.
+//! * `calendar` draws a contribution calendar with a row per weekday and a
+//!   column per week.
+//!
+//! The charts use Unicode blocks and ANSI colours when writing to a terminal
+//! and plain ASCII otherwise.
+//!
+//! #### `--columns`
+//!
+//! The columns of the output file, separated by commas. The available columns
+//! are `date`, `period`, `start`, `end`, `commit`, `author`, `email`,
+//! `subject`, `commits`, `added`, `removed`, `net`, `churn`, `hours`,
+//! `authors` and `binary-files`. The columns `commit`, `author`, `email` and
+//! `subject` require `--per-commit`.
+//!
+//! Defaults to the date, the commits, the LOC diff and the hours per day, to
+//! the period with its first and last day instead of the date when aggregating
+//! by longer periods, and to the commit, the date, the author, the email, the
+//! subject, the inserted and deleted lines, the LOC diff and the hours with
+//! `--per-commit`. The column `net` keeps the title `Loc` of earlier versions.
+//!
 //! #### `--commit-contains`, `-c`
 //!
 //! Filters for certain commit hashes. ORs if specified multiple times.
@@ -80,6 +187,57 @@
 //!
 //! Filters for certain commit hashes. ORs if specified multiple times.
 //!
+//! #### `--coupling`
+//!
+//! Lists the pairs of files or directories which change together in the same
+//! commits, even if they do not reference each other, one of `file` and
+//! `directory`. For every pair, the list shows the number of commits changing
+//! both, the support, i.e. the share of all commits changing both, the
+//! confidence in both directions, i.e. the share of the commits changing one
+//! path which also change the other, and the lift, i.e. how much more often
+//! both change together than expected if they were independent. Renamed files
+//! are tracked by their latest path. The list is also added to the reports and
+//! limited by `--top`.
+//!
+//! #### `--coupling-depth`
+//!
+//! The number of path components to aggregate directories by with `--coupling
+//! directory` [default: 2].
+//!
+//! #### `--coupling-dot`
+//!
+//! An output file for the coupling graph in Graphviz DOT format, with an edge
+//! per coupled pair. The edges are labelled with the number of commits changing
+//! both paths and drawn the thicker the higher their confidence. Analyses files
+//! unless `--coupling directory` is given.
+//!
+//! ```bash
+//! dot -Tsvg coupling.dot > coupling.svg
+//! ```
+//!
+//! #### `--coupling-max-files`
+//!
+//! Skips commits changing more than the given number of files when analysing
+//! the coupling [default: 30], such as reformattings or dependency updates
+//! which would couple everything. The files are counted before collapsing
+//! them into directories, such that `--coupling directory` skips the same
+//! commits.
+//!
+//! #### `--coupling-min-commits`
+//!
+//! The minimum number of commits changing both paths of a coupled pair
+//! [default: 2].
+//!
+//! #### `--coupling-min-confidence`
+//!
+//! The minimum confidence of a coupled pair in at least one direction, between
+//! 0 and 1 [default: 0.5].
+//!
+//! #### `--delimiter`
+//!
+//! The character separating the fields of the CSV files. Defaults to a comma.
+//! Double quotes and line breaks are not allowed.
+//!
 //! #### `--duration`, `-d`
 //!
 //! The time which may pass between two commits that still counts as working
@@ -93,15 +251,155 @@
 //!
 //! Filters for certain author emails. ORs if specified multiple times.
 //!
+//! #### `--explain`, `-x`
+//!
+//! Explains for every commit which filter clauses accepted or rejected it,
+//! whether the time since the previous matching commit was added to a session
+//! or broke it, and how much LOC each file contributed.
+//!
 //! #### `--file-extension`, `-f`
 //!
 //! Filters the LOC diff for a certain file extension (e.g. `--file-extension
 //! cpp`). ORs if specified multiple times.
 //!
+//! #### `--format`
+//!
+//! The format of the report printed to `stdout`, one of `text`, `json`,
+//! `markdown` and `html` [default: text]. All formats but `text` replace the
+//! text output entirely.
+//!
+//! * `json` prints a versioned document with the totals, the aggregates per
+//!   author and period, the sessions, a punch card, the configuration used and
+//!   any parse warnings.
+//! * `markdown` prints the totals and a table per author, period and session.
+//! * `html` prints a single page with the same tables and embedded charts of
+//!   the daily hours, commits and LOC, the hours per author and a punch card.
+//!   It does not need any external assets and can, hence, be attached to an
+//!   email.
+//!
+//! #### `--group-by`
+//!
+//! Groups the commits and the working time by any combination of the following
+//! dimensions, separated by commas, and computes the `--group-metrics` per
+//! group. The groups are printed as a table, added to the report and written to
+//! the output file instead of the periods. Cannot be combined with
+//! `--author-columns`, `--columns` and `--per-commit`.
+//!
+//! * `author`, `email` and `domain`, the domain of the email address. The
+//!   authors are merged like in `--authors`, the email addresses are compared
+//!   in lower case.
+//! * `commit`, `subject` and `category`, which is one of `feature`, `fix`,
+//!   `docs`, `test`, `refactor`, `chore`, `merge` and `other`. Conventional
+//!   prefixes such as `fix:` are respected, otherwise the first word of the
+//!   subject decides, e.g. `Add` or `Fixed`.
+//! * `type` and `scope` of a Conventional Commits header, see `--type`. The
+//!   type of commits without a header is `(none)`.
+//! * `day`, `week`, `month`, `year`, `weekday` and `hour` of the commit.
+//! * `file`, `extension`, `directory`, the top-level directory of a changed
+//!   file, and `language`, see `--language`. These create a group per changed
+//!   file, the time is distributed over the files as set by
+//!   `--time-attribution`.
+//! * `repository`, see `--repository`.
+//!
+//! ```bash
+//! commit-analyzer --group-by author,month git-history
+//! ```
+//!
+//! #### `--group-by-period`, `-g`
+//!
+//! The length of the periods to aggregate the output file by, one of `day`,
+//! `week`, `month`, `quarter` and `year` [default: day]. Weeks are labelled by
+//! their ISO week number if they start on Monday.
+//!
+//! #### `--group-metrics`
+//!
+//! The metrics to compute per group of `--group-by`, separated by commas.
+//! Defaults to all of them: `commits`, `hours`, `added`, `removed`, `net`,
+//! `churn`, `files` for the number of distinct changed files and `active-days`.
+//!
 //! #### `--help`, `-h`
 //!
 //! Print help information.
 //!
+//! #### `--hotspot-depth`
+//!
+//! The number of path components to aggregate the directories of the hotspots
+//! by [default: 2]. Files in the root directory belong to `.`.
+//!
+//! #### `--hotspot-sort-by`
+//!
+//! The column to sort the hotspots by, one of `commits` (default), `added`,
+//! `removed`, `churn`, `authors` and `last-modified`. All columns are sorted
+//! descending.
+//!
+//! #### `--hotspots`
+//!
+//! Lists the changed files and their directories with the number of commits,
+//! the inserted and deleted lines, the number of distinct authors, who are
+//! merged like in `--authors`, and the date of the last change, which reveals
+//! hotspots for refactoring. Renamed files
+//! keep their history under their latest path. The lists are also added to the
+//! reports and limited by `--top`.
+//!
+//! #### `--hotspots-output`
+//!
+//! An output file for all hotspots in CSV format, with a row per file and per
+//! directory.
+//!
+//! #### `--ics-output`
+//!
+//! An output file for the detected work sessions in iCalendar format, with an
+//! event per session. The summary names the repository and the authors, the
+//! description lists the commit subjects and all times are given in UTC. Every
+//! event lasts at least 30 minutes, such that sessions with a single commit
+//! show up in calendar apps.
+//!
+//! #### `--language`
+//!
+//! Filters the LOC diff for a certain language (e.g. `--language Rust`),
+//! ignoring the case. ORs if specified multiple times. Files are classified by
+//! their name or extension, similar to GitHub's Linguist, see
+//! `--language-override`. Unknown files belong to `Other`.
+//!
+//! #### `--language-override`
+//!
+//! Assigns the files matching a pattern to a language, in the form
+//! `<pattern>=<language>`. The pattern is either `*.` followed by an extension,
+//! ignoring the case, a directory ending with `/` or a file name. Can be
+//! specified multiple times, the first matching override wins over the built-in
+//! table.
+//!
+//! ```bash
+//! commit-analyzer --language-override "*.tpl=HTML" \
+//!     --language-override "vendor/=Vendored" --languages git-history
+//! ```
+//!
+//! #### `--languages`
+//!
+//! Lists the changed files, commits, inserted and deleted lines and the working
+//! time per language, sorted by the churn. The time is distributed over the
+//! files as set by `--time-attribution`. The table is also added to the report.
+//!
+//! #### `--loc-metric`
+//!
+//! The number the inserted and deleted lines are summed up to in the headline
+//! numbers, the sessions and the reports. This is one of:
+//!
+//! * `net` subtracts the deleted from the inserted lines (default).
+//! * `churn` adds the deleted to the inserted lines, such that refactorings
+//!   are not reported as zero.
+//! * `added` counts the inserted lines.
+//! * `removed` counts the deleted lines.
+//!
+//! The JSON report and the CSV files always list the inserted and deleted lines
+//! separately.
+//!
+//! #### `--merge-author-names`
+//!
+//! Considers identities sharing a name the same author in `--authors`, in
+//! addition to those sharing an email address. Different people may have the
+//! same name, hence this is off by default.
+//!
 //! #### `--message-starts-with`, `-l`
 //!
 //! Filters for certain commit messages. ORs if specified multiple times.
@@ -116,12 +414,304 @@
 //!
 //! #### `--output`, `-o`
 //!
-//! An output file for the commits per day in CSV format.
+//! An output file for the commits and hours per day in CSV format. With
+//! `--group-by`, the file holds one row per group instead.
+//!
+//! #### `--ownership`
+//!
+//! Lists the main owner of every changed file and directory. The changes of a
+//! path are attributed to their authors, who are merged like in `--authors`,
+//! weighted by the changed lines or the number of commits, see
+//! `--ownership-weight`. The table shows the owner's
+//! share of the path, the number of authors, the bus factor, i.e. the smallest
+//! number of authors holding the majority of the history, and the date of the
+//! owner's last commit. Owners who have not committed for a while are flagged
+//! as stale, see `--ownership-stale-after`. The paths with the lowest bus
+//! factor come first.
+//!
+//! #### `--ownership-as-of`
+//!
+//! The date to measure the staleness of owners from, e.g. `2022-06-30`. By
+//! default, it is today, such that an owner who left a year ago is flagged even
+//! if the analysed history ends with their last commit. Set it to reproduce an
+//! earlier report.
+//!
+//! #### `--ownership-depth`
+//!
+//! The number of path components to aggregate the directories of the ownership
+//! analysis by [default: 2]. Files in the root directory belong to `.`.
+//!
+//! #### `--ownership-half-life`
+//!
+//! The number of days after which the weight of a change is halved in the
+//! ownership analysis, measured from the last commit. Older changes hence count
+//! less, such that the ownership follows who recently worked on a path. The
+//! half-life has to be a positive number of days. By default, all changes count
+//! the same.
+//!
+//! #### `--ownership-stale-after`
+//!
+//! The number of days after which an owner without any commit, measured from
+//! the date given by `--ownership-as-of`, is flagged as stale [default: 180].
+//!
+//! #### `--ownership-weight`
+//!
+//! What a change is weighted by in the ownership analysis, either `loc`
+//! (default) for the inserted and deleted lines, at least one per change, or
+//! `commits` for the number of commits.
+//!
+//! #### `--per-commit`
+//!
+//! Writes one row per commit to the output file instead of one per period. The
+//! time between two commits of a session is attributed to the later one.
+//!
+//! #### `--punch-card`
+//!
+//! Shows a table with a row per weekday and a column per hour of the day, in
+//! the time zone given by `--timezone`. The cells count either the `commits` or
+//! the working `minutes`, where the time between two commits of a session is
+//! split at the full hours.
+//!
+//! #### `--punch-card-output`
+//!
+//! An output file for the commits and the working minutes per weekday and hour
+//! of the day in CSV format, with a row per weekday and hour.
+//!
+//! #### `--repository`
+//!
+//! The name of the repository in exports, such as the iCalendar events.
+//! Defaults to the name of the Git working directory or the stem of the log
+//! file.
+//!
+//! #### `--scope`
+//!
+//! Filters for certain Conventional Commits scopes (e.g. `--scope parser` for
+//! `feat(parser): support arrays`), ignoring the case. ORs if specified
+//! multiple times. Commits without a scope never match.
+//!
+//! #### `--sessions`, `-s`
+//!
+//! Lists every detected work session with its start, end, duration, authors,
+//! number of commits, LOC and the first commit subjects.
+//!
+//! #### `--sessions-output`
+//!
+//! An output file for the detected work sessions in CSV format.
+//!
+//! #### `--sort-by`
+//!
+//! The column to sort the authors by, one of `name`, `hours` (default),
+//! `share`, `sessions`, `commits`, `active-days`, `added`, `removed`, `loc`,
+//! `first-commit` and `last-commit`. The `loc` column is counted as set by
+//! `--loc-metric`. Names are sorted alphabetically, all other columns
+//! descending.
+//!
+//! #### `--sqlite-output`
+//!
+//! An output file for a SQLite database with all parsed commits, regardless of
+//! the filters, their file changes, the authors, the detected work sessions and
+//! the commits which could not be parsed. An existing file is only replaced
+//! once the new database is complete, commits appearing several times in the
+//! log are stored once. Dates are stored in UTC as `YYYY-MM-DD HH:MM:SS`.
+//!
+//! * `authors` holds every distinct pair of name and email address.
+//! * `commits` holds the hash, author, date, offset to UTC in seconds, merge
+//!   information, subject, Conventional Commits type, scope and breaking flag
+//!   and message of every commit as well as its session and the hours
+//!   attributed to it.
+//! * `file_changes` holds the old and new path as well as the inserted and
+//!   deleted lines of every changed file, which are `NULL` for binary files.
+//! * `sessions` holds the start, end, hours and number of commits.
+//! * `parse_errors` holds the line and reason of every skipped commit.
+//!
+//! ```sql
+//! SELECT authors.name, SUM(commits.hours)
+//! FROM commits JOIN authors ON authors.id = commits.author_id
+//! GROUP BY authors.name;
+//! ```
+//!
+//! #### `--sprint-calendar`
+//!
+//! A file with named periods, such as sprints, to aggregate the output file by.
+//! Each line holds a name, the first and the last day, separated by commas.
+//! Empty lines and lines starting with `#` are ignored, days outside of every
+//! period are left out.
+//!
+//! ```text
+//! # Name, first day, last day
+//! Sprint 1, 2022-06-01, 2022-06-14
+//! Sprint 2, 2022-06-15, 2022-06-28
+//! ```
+//!
+//! #### `--svg-chart`
+//!
+//! The charts to export with `--svg-output`, may be specified multiple times.
+//! Defaults to all of them.
+//!
+//! * `hours` draws a bar per period with the estimated hours, see
+//!   `--group-by-period`.
+//! * `loc` draws the cumulative net LOC diff per day.
+//! * `authors` stacks the estimated hours per period of every author.
+//! * `heatmap` draws a calendar with a cell per day, shaded by the estimated
+//!   hours.
+//!
+//! #### `--svg-output`
+//!
+//! A directory to export charts to as SVG files, named after the chart, e.g.
+//! `hours.svg`. The charts do not need any external tools or assets.
+//!
+//! #### `--svg-size`
+//!
+//! The width and height of the exported charts in pixels [default: 720x240].
+//! Charts need at least 161x101 pixels to leave room for the plot besides the
+//! margins and labels.
+//!
+//! #### `--ticket-pattern`
+//!
+//! A regular expression matching ticket references in the commit messages, for
+//! `--tickets`. If it contains a capture group, the first group is the key of
+//! the ticket, such that different spellings can be normalised. Can be
+//! specified multiple times. By default, keys like `PROJ-123` and `GH-78`, i.e.
+//! `\b[A-Z]{2}[A-Z0-9_]*-[0-9]+\b`, and `#456`, i.e. `#[0-9]+\b`, are found.
+//! Well-known standards which look like keys, such as `UTF-8`, `SHA-256` or
+//! `ISO-8601`, are ignored by these defaults. Their prefixes are `AES`, `CRC`,
+//! `CVE`, `ECMA`, `IEEE`, `ISO`, `PEP`, `RFC`, `RSA`, `SHA`, `TLS`, `UCS` and
+//! `UTF`.
+//!
+//! ```bash
+//! commit-analyzer --tickets --ticket-pattern "(?:GH-|#)([0-9]+)" git-history
+//! ```
+//!
+//! #### `--tickets`
+//!
+//! Lists the commits, inserted and deleted lines and the working time per
+//! ticket referenced in the commit messages, see `--ticket-pattern`. The whole
+//! message is searched, including the body and the messages of merge commits,
+//! which usually name the merged branch. The time is distributed over the
+//! commits as set by `--time-attribution`, the time of a commit referencing
+//! several tickets is split evenly between them. Commits without a reference
+//! belong to the tickets of the previous commit of their session or, at the
+//! beginning of a session, of the next one. The remaining work is listed as
+//! `(none)`. The table is also added to the report.
+//!
+//! #### `--tickets-output`
+//!
+//! The CSV file to export the working time per ticket to, see `--tickets`. The
+//! unreferenced work has an empty key.
+//!
+//! #### `--time-attribution`
+//!
+//! How the working time is distributed over the commits and their changed files
+//! matching the filters, for `--time-by`, `--tickets`, `--languages` and the
+//! file dimensions of `--group-by` and `query`. Without a file dimension, a
+//! commit is always attributed the time since the previous commit of its
+//! session, such that the hours per author and per day match `--authors` and
+//! `--sessions`. This is one of:
+//!
+//! * `even` (default) splits the duration of a session evenly between its
+//!   commits changing any files, then evenly between their files.
+//! * `interval` attributes the time since the previous commit of a session to
+//!   a commit and splits it evenly between its files.
+//! * `churn` splits the duration of a session between the files of its commits
+//!   by their inserted and deleted lines. Sessions which only changed binary
+//!   files are split evenly.
+//!
+//! With `even` and `churn`, the whole estimated time is attributed to files,
+//! unless a session does not change any files matching the filters. With
+//! `interval`, the time of the commits which do not change any matching files
+//! stays with these commits. Whenever time cannot be attributed to a file, it
+//! is listed as `(no files)` by `--time-by`, `--languages` and the file
+//! dimensions of `--group-by` and `query`, such that the hours still add up to
+//! the estimated time.
+//!
+//! #### `--time-by`
+//!
+//! Distributes the working time over the changed files and lists the time spent
+//! per `file`, `directory` or `language`, sorted by the hours. The share refers
+//! to the whole estimated time. Renamed files are tracked by their latest path.
+//! The table is also added to the report.
+//!
+//! ```bash
+//! commit-analyzer --time-by directory --time-attribution churn git-history
+//! ```
+//!
+//! #### `--time-depth`
+//!
+//! The number of path components to aggregate the directories of `--time-by` by
+//! [default: 2]. Files in the root directory belong to `.`.
+//!
+//! #### `--timezone`, `-z`
+//!
+//! The time zone to convert all dates into before they are bucketed into days,
+//! split at midnight and displayed, e.g. `utc`, `local`, `+02:00` or
+//! `Europe/Berlin` [default: original]. By default, each date keeps the offset
+//! it was recorded with. The original offsets stay available in the sessions
+//! output file.
+//!
+//! #### `--top`
+//!
+//! Limits the lists of authors, hotspots, coupled pairs, owned areas, time
+//! shares and tickets, also in the reports, to the given number of entries.
+//!
+//! #### `--tracker`
+//!
+//! The time tracker to export the detected work sessions for, requires
+//! `--tracker-output`. Like for `--authors`, the time since the previous commit
+//! of a session belongs to the author of the next one, and the consecutive work
+//! of an author becomes a time entry. Hence, the entries add up to the
+//! estimated time. This is one of:
+//!
+//! * `toggl` writes the CSV import of Toggl Track.
+//! * `clockify` writes the CSV import of Clockify.
+//! * `harvest` writes the CSV import of Harvest.
+//! * `timewarrior` writes the JSON import of Timewarrior, where the project
+//!   becomes the first tag.
+//!
+//! #### `--tracker-description`
+//!
+//! The field the description of a time entry is taken from, one of
+//! `repository`, `authors` and `subjects` [default: subjects].
+//!
+//! #### `--tracker-output`
+//!
+//! An output file for the time entries of `--tracker`.
+//!
+//! #### `--tracker-project`
+//!
+//! The field the project of a time entry is taken from, see
+//! `--tracker-description` [default: repository].
+//!
+//! #### `--tracker-tags`
+//!
+//! The fields the tags of a time entry are taken from, see
+//! `--tracker-description`. May be specified multiple times and defaults to the
+//! authors.
+//!
+//! #### `--type`
+//!
+//! Filters for certain Conventional Commits types (e.g. `--type feat`),
+//! ignoring the case. ORs if specified multiple times. The header of a
+//! message consists of the type, an optional scope in parentheses, an optional
+//! `!` for breaking changes, a colon and the description, e.g. `fix(parser)!:
+//! reject empty input`. Commits without such a header never match.
+//!
+//! #### `--types`
+//!
+//! Lists the commits, inserted and deleted lines and the working time per
+//! Conventional Commits type, such as `feat`, `fix`, `refactor` and `chore`,
+//! sorted by the hours. Commits without a conventional header are listed as
+//! `(none)`. A commit is attributed the time since the previous commit of its
+//! session, like in `--authors`. The table is also added to the report.
 //!
 //! #### `--verbose`, `-v`
 //!
 //! Always shows the entire output.
 //!
+//! #### `--week-start`
+//!
+//! The first day of a week when aggregating by week and in the contribution
+//! calendar [default: monday].
+//!
 //! #### `--version`, `-V`
 //!
 //! Print version information.
@@ -140,6 +730,48 @@
 //!
 //! Reads the specified input file.
 //!
+//! #### `query`
+//!
+//! Runs a query over the commits instead of printing the report. The query is
+//! followed by one of the other subcommands, which reads the commits. All
+//! filters apply. The result is printed as a table or, with `--format json`,
+//! as JSON and written as CSV to `--output`. The other output files cannot be
+//! combined with a query.
+//!
+//! ```bash
+//! commit-analyzer query \
+//!     "group by author, month select sum(hours), count(), sum(added)" \
+//!     git-history
+//! ```
+//!
+//! A query consists of the following clauses, each optional and at most once:
+//!
+//! * `where <condition> [and <condition>]...` keeps the matching records, e.g.
+//!   `where subject ~ fix and net > 0`. The operators are `=`, `!=`, `<`, `<=`,
+//!   `>`, `>=` and `~` for texts containing the value, ignoring the case.
+//!   Values consisting of digits, such as those of `hour` and `year`, are
+//!   compared as numbers.
+//! * `group by <dimension>, ...` combines the records with the same values.
+//! * `select <column> [as <title>], ...` chooses the columns after the
+//!   dimensions, by default `count()` and `sum(hours)`. The titles need to be
+//!   distinct.
+//! * `order by <column> [asc|desc], ...` sorts the rows by a column or its
+//!   title, by default the rows are sorted by the dimensions. Weekdays are
+//!   sorted from Monday to Sunday, like in `--group-by`.
+//! * `limit <number>` keeps the first rows.
+//!
+//! The dimensions are the ones of `--group-by`, `date` is an alias for `day`.
+//! The fields are `hours`, `added`, `removed`, `net`, `churn` and `files`. The
+//! columns aggregate them with `count()`, `count(<column>)` for the number of
+//! distinct values, `sum(<field>)`, `avg(<field>)`, `min(<column>)` and
+//! `max(<column>)`.
+//!
+//! A record is a commit or, as soon as the query refers to a file, extension,
+//! directory or language, a changed file. A commit is attributed the time since
+//! the previous commit of its session, like in `--authors`, such that the hours
+//! per author match. The time is distributed over the files as set by
+//! `--time-attribution`.
+//!
 //! #### `stdin`
 //!
 //! Reads from `stdin`.
@@ -160,6 +792,32 @@
 //!
 //! <!------------------------------------------------------------------------->
 
+pub mod activity;
+pub mod attribution;
+pub mod authors;
+pub mod chart;
+pub mod conventional;
+pub mod coupling;
+pub mod csv;
+pub mod explain;
+pub mod group;
+pub mod hotspots;
+pub mod ical;
+pub mod language;
+pub mod loc;
+pub mod ownership;
+pub mod period;
+pub mod punchcard;
+pub mod query;
+pub mod report;
+pub mod session;
+pub mod sqlite;
+pub mod svg;
+pub mod table;
+pub mod tickets;
+pub mod timezone;
+pub mod tracker;
+
 use std::path::PathBuf;
 
 use clap::{Parser, Subcommand};
@@ -168,113 +826,851 @@ use clap::{Parser, Subcommand};
 #[derive(Parser, Debug)]
 #[clap(version, about, long_about = None)]
 pub struct Args {
-    /// Specifies how the program will read the Git history.
+    /// Specifies what the program will do and how it will read the Git
+    /// history.
     #[clap(subcommand)]
-    input_method: InputMethod,
+    command: Command,
 
     /// Always shows the entire output.
     #[clap(short = 'v', long = "verbose")]
     is_verbose: bool,
 
-    /// Filters the LOC diff for a certain file extension (e.g.
-    /// `--file-extension cpp`). ORs if specified multiple times.
-    #[clap(short, long)]
-    file_extension: Vec<String>,
+    /// The format of the report printed to `stdout`.
+    #[clap(long, value_enum, default_value_t = crate::report::Format::Text)]
+    format: crate::report::Format,
+
+    /// Explains for every commit why and how it was counted.
+    #[clap(short = 'x', long)]
+    explain: bool,
+
+    /// Filters the LOC diff for a certain file extension (e.g.
+    /// `--file-extension cpp`). ORs if specified multiple times.
+    #[clap(short, long)]
+    file_extension: Vec<String>,
+
+    /// Filters the LOC diff for a certain language (e.g. `--language Rust`),
+    /// ignoring the case. ORs if specified multiple times.
+    #[clap(long)]
+    language: Vec<String>,
+
+    /// Assigns the files matching a pattern to a language (e.g.
+    /// `--language-override '*.tpl=HTML'`). The first matching override wins.
+    #[clap(long)]
+    language_override: Vec<crate::language::Override>,
+
+    /// The number the inserted and deleted lines are summed up to in the
+    /// headline numbers.
+    #[clap(long, value_enum, default_value_t = crate::loc::LocMetric::Net)]
+    loc_metric: crate::loc::LocMetric,
+
+    /// The time which may pass between two commits that still counts as working.
+    #[clap(short, long, default_value_t = 3)]
+    duration: u32,
+
+    /// An output file for the commits and hours per day in CSV format.
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+
+    /// Adds a column with the hours of every author to the output file.
+    #[clap(long)]
+    author_columns: bool,
+
+    /// The columns of the output file, separated by commas. Defaults to the
+    /// date, the commits, the LOC diff and the hours, or to the commit, the
+    /// date, the author, the subject and the LOC and hours per commit.
+    #[clap(long, value_enum, value_delimiter = ',')]
+    columns: Vec<crate::csv::Column>,
+
+    /// The character separating the fields of the CSV files.
+    #[clap(long, default_value_t)]
+    delimiter: crate::csv::Delimiter,
+
+    /// Writes one row per commit to the output file instead of one per period.
+    #[clap(long)]
+    per_commit: bool,
+
+    /// The length of the periods to aggregate the output file by.
+    #[clap(short, long, value_enum, default_value_t = crate::period::Granularity::Day)]
+    group_by_period: crate::period::Granularity,
+
+    /// Groups the commits and the working time by the given dimensions,
+    /// separated by commas. The output file then holds one row per group.
+    #[clap(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        conflicts_with_all = &["per-commit", "columns", "author-columns"]
+    )]
+    group_by: Vec<crate::group::Dimension>,
+
+    /// The metrics to compute per group, separated by commas. Defaults to all
+    /// of them.
+    #[clap(long, value_enum, value_delimiter = ',', requires = "group-by")]
+    group_metrics: Vec<crate::group::Metric>,
+
+    /// The first day of a week when aggregating by week and in the
+    /// contribution calendar.
+    #[clap(long, value_enum, default_value_t = crate::period::WeekStart::Monday)]
+    week_start: crate::period::WeekStart,
+
+    /// A file with named periods, such as sprints, to aggregate the output
+    /// file by. Each line holds a name, the first and the last day, separated
+    /// by commas.
+    #[clap(long, conflicts_with = "group-by-period")]
+    sprint_calendar: Option<PathBuf>,
+
+    /// The time zone to convert all dates into, e.g. `utc`, `local`, `+02:00`
+    /// or `Europe/Berlin`. By default, each date keeps its original offset.
+    #[clap(
+        short = 'z',
+        long,
+        allow_hyphen_values = true,
+        default_value = "original"
+    )]
+    timezone: crate::timezone::Timezone,
+
+    /// Filters for certain author names. ORs if specified multiple times.
+    #[clap(short, long)]
+    author_contains: Vec<String>,
+
+    /// Filters for certain author names. ORs if specified multiple times.
+    #[clap(long)]
+    author_equals: Vec<String>,
+
+    /// Filters for certain author emails. ORs if specified multiple times.
+    #[clap(short, long)]
+    email_contains: Vec<String>,
+
+    /// Filters for certain author emails. ORs if specified multiple times.
+    #[clap(long)]
+    email_equals: Vec<String>,
+
+    /// Filters for certain commit hashes. ORs if specified multiple times.
+    #[clap(short, long)]
+    commit_contains: Vec<String>,
+
+    /// Filters for certain commit hashes. ORs if specified multiple times.
+    #[clap(long)]
+    commit_equals: Vec<String>,
+
+    /// Filters for certain commit messages. ORs if specified multiple times.
+    #[clap(short, long)]
+    message_contains: Vec<String>,
+
+    /// Filters for certain commit messages. ORs if specified multiple times.
+    #[clap(long)]
+    message_equals: Vec<String>,
+
+    /// Filters for certain commit messages. ORs if specified multiple times.
+    #[clap(short = 'l', long)]
+    message_starts_with: Vec<String>,
+
+    /// Filters for certain Conventional Commits types (e.g. `--type feat`),
+    /// ignoring the case. ORs if specified multiple times.
+    #[clap(long = "type")]
+    commit_type: Vec<String>,
+
+    /// Filters for certain Conventional Commits scopes (e.g. `--scope
+    /// parser`), ignoring the case. ORs if specified multiple times.
+    #[clap(long)]
+    scope: Vec<String>,
+
+    /// Lists the estimated hours, sessions, commits, active days, LOC and the
+    /// first and last commit of every author.
+    #[clap(long)]
+    authors: bool,
+
+    /// The column to sort the authors by.
+    #[clap(long, value_enum, default_value_t = crate::authors::SortKey::Hours)]
+    sort_by: crate::authors::SortKey,
+
+    /// Considers identities sharing a name the same author, in addition to
+    /// those sharing an email address.
+    #[clap(long)]
+    merge_author_names: bool,
+
+    /// Limits the lists of authors, hotspots, coupled pairs, owned areas, time
+    /// shares and tickets to the given number of entries.
+    #[clap(long)]
+    top: Option<usize>,
+
+    /// Lists the files and directories with their commits, inserted and
+    /// deleted lines, distinct authors and last change.
+    #[clap(long)]
+    hotspots: bool,
+
+    /// The number of path components to aggregate the directories of the
+    /// hotspots by.
+    #[clap(long, default_value_t = 2)]
+    hotspot_depth: usize,
+
+    /// The column to sort the hotspots by.
+    #[clap(long, value_enum, default_value_t = crate::hotspots::SortKey::Commits)]
+    hotspot_sort_by: crate::hotspots::SortKey,
+
+    /// An output file for the hotspots in CSV format.
+    #[clap(long)]
+    hotspots_output: Option<PathBuf>,
+
+    /// Lists the commits, LOC and working time per referenced ticket.
+    #[clap(long)]
+    tickets: bool,
+
+    /// A regular expression matching ticket references. If it contains a
+    /// capture group, the first group is the ticket's key. Can be specified
+    /// multiple times. By default, keys like `PROJ-123` and `#456` are found.
+    #[clap(long)]
+    ticket_pattern: Vec<crate::tickets::Pattern>,
+
+    /// The CSV file to export the working time per ticket to.
+    #[clap(long)]
+    tickets_output: Option<PathBuf>,
+
+    /// Distributes the working time over the changed files and lists the
+    /// time spent per file, directory or language.
+    #[clap(long, value_enum)]
+    time_by: Option<crate::attribution::Level>,
+
+    /// The number of path components to aggregate the directories of
+    /// `--time-by` by.
+    #[clap(long, default_value_t = 2)]
+    time_depth: usize,
+
+    /// How the working time is distributed over the commits and their files.
+    #[clap(long, value_enum, default_value_t = crate::attribution::Attribution::Even)]
+    time_attribution: crate::attribution::Attribution,
+
+    /// Lists the commits, LOC and working time per Conventional Commits type.
+    #[clap(long)]
+    types: bool,
+
+    /// Lists the changed files, commits, LOC and working time per language.
+    #[clap(long)]
+    languages: bool,
+
+    /// Lists the main owner, their share and the bus factor of every file and
+    /// directory.
+    #[clap(long)]
+    ownership: bool,
+
+    /// The number of path components to aggregate the directories of the
+    /// ownership analysis by.
+    #[clap(long, default_value_t = 2)]
+    ownership_depth: usize,
+
+    /// The number of days after which the weight of a change is halved in
+    /// the ownership analysis. By default, all changes count the same.
+    #[clap(long)]
+    ownership_half_life: Option<crate::ownership::HalfLife>,
+
+    /// What a change is weighted by in the ownership analysis.
+    #[clap(long, value_enum, default_value_t = crate::ownership::Weight::Loc)]
+    ownership_weight: crate::ownership::Weight,
+
+    /// The number of days without a commit after which an owner is flagged
+    /// as stale.
+    #[clap(long, default_value_t = 180)]
+    ownership_stale_after: i64,
+
+    /// The date to measure the staleness of owners from, e.g. `2022-06-30`.
+    /// Defaults to today.
+    #[clap(long)]
+    ownership_as_of: Option<chrono::NaiveDate>,
+
+    /// Lists the pairs of files or directories which change together.
+    #[clap(long, value_enum)]
+    coupling: Option<crate::coupling::CouplingLevel>,
+
+    /// The number of path components to aggregate directories by when
+    /// analysing their coupling.
+    #[clap(long, default_value_t = 2)]
+    coupling_depth: usize,
+
+    /// Skips commits changing more than the given number of files when
+    /// analysing the coupling, even if they belong to fewer directories.
+    #[clap(long, default_value_t = 30)]
+    coupling_max_files: usize,
+
+    /// The minimum number of commits changing both paths of a coupled pair.
+    #[clap(long, default_value_t = 2)]
+    coupling_min_commits: usize,
+
+    /// The minimum confidence of a coupled pair, between 0 and 1.
+    #[clap(long, default_value = "0.5")]
+    coupling_min_confidence: crate::coupling::Confidence,
+
+    /// An output file for the coupling graph in Graphviz DOT format.
+    #[clap(long)]
+    coupling_dot: Option<PathBuf>,
+
+    /// Draws a chart of the hours per day or period in the terminal. One of
+    /// `sparkline`, `bars` and `calendar`, may be specified multiple times.
+    #[clap(long, value_enum)]
+    chart: Vec<crate::chart::Chart>,
+
+    /// Shows the commits or the working time per weekday and hour of the day.
+    #[clap(long, value_enum)]
+    punch_card: Option<crate::punchcard::PunchCardValue>,
+
+    /// An output file for the commits and the working time per weekday and
+    /// hour of the day in CSV format.
+    #[clap(long)]
+    punch_card_output: Option<PathBuf>,
+
+    /// A directory to export charts to as SVG files.
+    #[clap(long)]
+    svg_output: Option<PathBuf>,
+
+    /// The charts to export as SVG files, one of `hours`, `loc`, `authors`
+    /// and `heatmap`. Defaults to all of them.
+    #[clap(long, value_enum)]
+    svg_chart: Vec<crate::svg::Chart>,
+
+    /// The width and height of the exported charts in pixels, at least
+    /// 161x101.
+    #[clap(long, default_value = "720x240")]
+    svg_size: crate::svg::Size,
+
+    /// An output file for the detected work sessions in iCalendar format.
+    #[clap(long)]
+    ics_output: Option<PathBuf>,
+
+    /// The name of the repository in exports. Defaults to the name of the
+    /// working directory or the log file.
+    #[clap(long)]
+    repository: Option<String>,
+
+    /// An output file for a SQLite database with all commits, file changes,
+    /// sessions and parse errors. An existing file is replaced.
+    #[clap(long)]
+    sqlite_output: Option<PathBuf>,
+
+    /// The time tracker to export the detected work sessions for.
+    ///
+    /// The consecutive work of an author within a session becomes a time
+    /// entry, such that the entries add up to the estimated time.
+    #[clap(long, value_enum, requires = "tracker-output")]
+    tracker: Option<crate::tracker::TrackerFormat>,
+
+    /// An output file for the time entries of `--tracker`.
+    #[clap(long, requires = "tracker")]
+    tracker_output: Option<PathBuf>,
+
+    /// The field the project of a time entry is taken from.
+    #[clap(long, value_enum, default_value_t = crate::tracker::Field::Repository)]
+    tracker_project: crate::tracker::Field,
+
+    /// The field the description of a time entry is taken from.
+    #[clap(long, value_enum, default_value_t = crate::tracker::Field::Subjects)]
+    tracker_description: crate::tracker::Field,
+
+    /// The fields the tags of a time entry are taken from. Defaults to the
+    /// authors.
+    #[clap(long, value_enum)]
+    tracker_tags: Vec<crate::tracker::Field>,
+
+    /// Lists every detected work session.
+    #[clap(short, long)]
+    sessions: bool,
+
+    /// An output file for the detected work sessions in CSV format.
+    #[clap(long)]
+    sessions_output: Option<PathBuf>,
+}
+
+impl Args {
+    /// Parses the command line arguments, exiting on invalid ones.
+    ///
+    /// In addition to the declared constraints, queries must not be combined
+    /// with the output files they do not write.
+    #[must_use]
+    pub fn parse_checked() -> Self {
+        let args = Self::parse();
+
+        if let (Some(_), Some(option)) = (args.query(), args.query_conflicts().first()) {
+            <Self as clap::CommandFactory>::command()
+                .error(
+                    clap::ErrorKind::ArgumentConflict,
+                    format!("The argument '{}' cannot be used with 'query'", option),
+                )
+                .exit();
+        }
+
+        args
+    }
+
+    /// Gets the input method specified by the user.
+    #[must_use]
+    pub fn input_method(&self) -> &InputMethod {
+        match &self.command {
+            Command::Report(input_method) => input_method,
+            Command::Query { input_method, .. } => input_method,
+        }
+    }
+
+    /// Gets the query to run instead of printing the report, if any.
+    #[must_use]
+    pub fn query(&self) -> Option<&str> {
+        match &self.command {
+            Command::Report(_) => None,
+            Command::Query { query, .. } => Some(query),
+        }
+    }
+
+    /// The given output options which queries do not write, as on the command
+    /// line.
+    fn query_conflicts(&self) -> Vec<&'static str> {
+        [
+            ("--coupling-dot", self.coupling_dot.is_some()),
+            ("--hotspots-output", self.hotspots_output.is_some()),
+            ("--ics-output", self.ics_output.is_some()),
+            ("--punch-card-output", self.punch_card_output.is_some()),
+            ("--sessions-output", self.sessions_output.is_some()),
+            ("--sqlite-output", self.sqlite_output.is_some()),
+            ("--svg-output", self.svg_output.is_some()),
+            ("--tickets-output", self.tickets_output.is_some()),
+            ("--tracker-output", self.tracker_output.is_some()),
+        ]
+        .into_iter()
+        .filter(|(_, given)| *given)
+        .map(|(option, _)| option)
+        .collect()
+    }
+
+    /// Gets the format of the report printed to `stdout`.
+    #[must_use]
+    pub fn format(&self) -> crate::report::Format {
+        self.format
+    }
+
+    /// Gets the configured verbosity level of the program.
+    #[must_use]
+    pub fn is_verbose(&self) -> bool {
+        self.is_verbose
+    }
+
+    /// Whether to add a column per author to the output file.
+    #[must_use]
+    pub fn author_columns(&self) -> bool {
+        self.author_columns
+    }
+
+    /// Gets the columns of the output file.
+    ///
+    /// If the user did not select any, the defaults for the given calendar are
+    /// returned.
+    #[must_use]
+    pub fn columns(&self, calendar: &crate::period::Calendar) -> Vec<crate::csv::Column> {
+        if !self.columns.is_empty() {
+            self.columns.clone()
+        } else if self.per_commit {
+            crate::csv::Column::per_commit()
+        } else {
+            crate::csv::Column::per_period(calendar)
+        }
+    }
+
+    /// Gets the character separating the fields of the CSV files.
+    #[must_use]
+    pub fn delimiter(&self) -> crate::csv::Delimiter {
+        self.delimiter
+    }
+
+    /// Gets the dimensions to group the commits and the working time by.
+    #[must_use]
+    pub fn group_by(&self) -> &[crate::group::Dimension] {
+        &self.group_by
+    }
+
+    /// Gets the metrics to compute per group.
+    ///
+    /// If the user did not select any, all metrics are returned.
+    #[must_use]
+    pub fn group_metrics(&self) -> Vec<crate::group::Metric> {
+        if self.group_metrics.is_empty() {
+            <crate::group::Metric as clap::ValueEnum>::value_variants().to_vec()
+        } else {
+            self.group_metrics.clone()
+        }
+    }
+
+    /// Whether to write one row per commit to the output file.
+    #[must_use]
+    pub fn per_commit(&self) -> bool {
+        self.per_commit
+    }
+
+    /// Creates the calendar of regular periods as specified by the user.
+    ///
+    /// The sprint calendar, if any, needs to be read separately, see
+    /// `Args::take_sprint_calendar`.
+    #[must_use]
+    pub fn calendar(&self) -> crate::period::Calendar {
+        crate::period::Calendar::Regular {
+            granularity: self.group_by_period,
+            week_start: self.week_start(),
+        }
+    }
+
+    /// Gets the terminal charts to draw.
+    #[must_use]
+    pub fn charts(&self) -> &[crate::chart::Chart] {
+        &self.chart
+    }
+
+    /// Whether to explain how every commit was counted.
+    #[must_use]
+    pub fn explain(&self) -> bool {
+        self.explain
+    }
+
+    /// Gets the maximum duration between two commits considered spent working.
+    #[must_use]
+    pub fn duration(&self) -> u32 {
+        self.duration
+    }
+
+    /// Gets the number the inserted and deleted lines are summed up to.
+    #[must_use]
+    pub fn loc_metric(&self) -> crate::loc::LocMetric {
+        self.loc_metric
+    }
+
+    /// Gets the time zone to convert all dates into.
+    #[must_use]
+    pub fn timezone(&self) -> crate::timezone::Timezone {
+        self.timezone
+    }
+
+    /// Gets the first day of a week.
+    #[must_use]
+    pub fn week_start(&self) -> chrono::Weekday {
+        self.week_start.into()
+    }
+
+    /// Moves the output path specified by the user out of `Args`
+    ///
+    /// This method moves the specified path to the intended output file out of
+    /// this struct by calling `Option::take`. This should, hence, be called
+    /// just once but prevents an obsolete clone.
+    #[must_use]
+    pub fn take_output(&mut self) -> Option<PathBuf> {
+        self.output.take()
+    }
+
+    /// Whether to list the summary of every author.
+    #[must_use]
+    pub fn authors(&self) -> bool {
+        self.authors
+    }
+
+    /// Gets the column to sort the authors by.
+    #[must_use]
+    pub fn sort_by(&self) -> crate::authors::SortKey {
+        self.sort_by
+    }
+
+    /// Gets the maximum number of authors, hotspots, coupled pairs, owned
+    /// areas, time shares and tickets to list.
+    #[must_use]
+    pub fn top(&self) -> Option<usize> {
+        self.top
+    }
+
+    /// Whether to list the hotspots among the files and directories.
+    #[must_use]
+    pub fn hotspots(&self) -> bool {
+        self.hotspots
+    }
+
+    /// Gets the number of path components to aggregate directories by.
+    #[must_use]
+    pub fn hotspot_depth(&self) -> usize {
+        self.hotspot_depth
+    }
+
+    /// Gets the column to sort the hotspots by.
+    #[must_use]
+    pub fn hotspot_sort_by(&self) -> crate::hotspots::SortKey {
+        self.hotspot_sort_by
+    }
+
+    /// Moves the tickets output path specified by the user out of `Args`
+    ///
+    /// See `Args::take_output` for details.
+    #[must_use]
+    pub fn take_tickets_output(&mut self) -> Option<PathBuf> {
+        self.tickets_output.take()
+    }
+
+    /// The patterns of ticket references as specified by the user or the
+    /// default ones.
+    #[must_use]
+    pub fn ticket_patterns(&self) -> Vec<crate::tickets::Pattern> {
+        if self.ticket_pattern.is_empty() {
+            crate::tickets::default_patterns()
+        } else {
+            self.ticket_pattern.clone()
+        }
+    }
+
+    /// Whether to list the working time per ticket.
+    #[must_use]
+    pub fn tickets(&self) -> bool {
+        self.tickets
+    }
+
+    /// The getter method for the field `time_attribution` of the
+    /// corresponding struct.
+    #[must_use]
+    pub fn time_attribution(&self) -> crate::attribution::Attribution {
+        self.time_attribution
+    }
+
+    /// The getter method for the field `time_by` of the corresponding struct.
+    #[must_use]
+    pub fn time_by(&self) -> Option<crate::attribution::Level> {
+        self.time_by
+    }
+
+    /// The getter method for the field `time_depth` of the corresponding
+    /// struct.
+    #[must_use]
+    pub fn time_depth(&self) -> usize {
+        self.time_depth
+    }
+
+    /// Whether to list the aggregates per Conventional Commits type.
+    #[must_use]
+    pub fn types(&self) -> bool {
+        self.types
+    }
+
+    /// Whether to list the aggregates per language.
+    #[must_use]
+    pub fn languages(&self) -> bool {
+        self.languages
+    }
+
+    /// Whether to list the ownership of the files and directories.
+    #[must_use]
+    pub fn ownership(&self) -> bool {
+        self.ownership
+    }
+
+    /// Creates the settings of the ownership analysis as specified by the
+    /// user.
+    #[must_use]
+    pub fn ownership_options(&self) -> crate::ownership::Options {
+        crate::ownership::Options {
+            as_of: self
+                .ownership_as_of
+                .unwrap_or_else(|| chrono::Local::today().naive_local()),
+            depth: self.ownership_depth,
+            half_life: self.ownership_half_life.map(f64::from),
+            stale_after: self.ownership_stale_after,
+            weight: self.ownership_weight,
+        }
+    }
+
+    /// Gets the paths to analyse the coupling of, if requested.
+    #[must_use]
+    pub fn coupling(&self) -> Option<crate::coupling::CouplingLevel> {
+        self.coupling
+    }
+
+    /// Gets the number of path components to aggregate directories by when
+    /// analysing their coupling.
+    #[must_use]
+    pub fn coupling_depth(&self) -> usize {
+        self.coupling_depth
+    }
+
+    /// Creates the thresholds of the coupling analysis as specified by the
+    /// user.
+    #[must_use]
+    pub fn coupling_thresholds(&self) -> crate::coupling::Thresholds {
+        crate::coupling::Thresholds {
+            max_files: self.coupling_max_files,
+            min_commits: self.coupling_min_commits,
+            min_confidence: self.coupling_min_confidence.into(),
+        }
+    }
 
-    /// The time which may pass between two commits that still counts as working.
-    #[clap(short, long, default_value_t = 3)]
-    duration: u32,
+    /// Moves the coupling graph output path specified by the user out of
+    /// `Args`
+    ///
+    /// See `Args::take_output` for details.
+    #[must_use]
+    pub fn take_coupling_dot(&mut self) -> Option<PathBuf> {
+        self.coupling_dot.take()
+    }
 
-    /// An output file for the commits per day in CSV format.
-    #[clap(short, long)]
-    output: Option<PathBuf>,
+    /// Moves the hotspots output path specified by the user out of `Args`
+    ///
+    /// See `Args::take_output` for details.
+    #[must_use]
+    pub fn take_hotspots_output(&mut self) -> Option<PathBuf> {
+        self.hotspots_output.take()
+    }
 
-    /// Filters for certain author names. ORs if specified multiple times.
-    #[clap(short, long)]
-    author_contains: Vec<String>,
+    /// Gets the values to show per weekday and hour of the day, if any.
+    #[must_use]
+    pub fn punch_card(&self) -> Option<crate::punchcard::PunchCardValue> {
+        self.punch_card
+    }
 
-    /// Filters for certain author names. ORs if specified multiple times.
-    #[clap(long)]
-    author_equals: Vec<String>,
+    /// Moves the punch card output path specified by the user out of `Args`
+    ///
+    /// See `Args::take_output` for details.
+    #[must_use]
+    pub fn take_punch_card_output(&mut self) -> Option<PathBuf> {
+        self.punch_card_output.take()
+    }
 
-    /// Filters for certain author emails. ORs if specified multiple times.
-    #[clap(short, long)]
-    email_contains: Vec<String>,
+    /// Gets the charts to export as SVG files.
+    #[must_use]
+    pub fn svg_charts(&self) -> Vec<crate::svg::Chart> {
+        if self.svg_chart.is_empty() {
+            <crate::svg::Chart as clap::ValueEnum>::value_variants().to_vec()
+        } else {
+            self.svg_chart.clone()
+        }
+    }
 
-    /// Filters for certain author emails. ORs if specified multiple times.
-    #[clap(long)]
-    email_equals: Vec<String>,
+    /// Gets the size of the exported charts.
+    #[must_use]
+    pub fn svg_size(&self) -> crate::svg::Size {
+        self.svg_size
+    }
 
-    /// Filters for certain commit hashes. ORs if specified multiple times.
-    #[clap(short, long)]
-    commit_contains: Vec<String>,
+    /// Moves the SVG output directory specified by the user out of `Args`
+    ///
+    /// See `Args::take_output` for details.
+    #[must_use]
+    pub fn take_svg_output(&mut self) -> Option<PathBuf> {
+        self.svg_output.take()
+    }
 
-    /// Filters for certain commit hashes. ORs if specified multiple times.
-    #[clap(long)]
-    commit_equals: Vec<String>,
+    /// Gets the name of the repository as specified by the user or guessed
+    /// from the input method.
+    #[must_use]
+    pub fn repository(&self) -> String {
+        self.repository
+            .clone()
+            .unwrap_or_else(|| self.input_method().repository())
+    }
 
-    /// Filters for certain commit messages. ORs if specified multiple times.
-    #[clap(short, long)]
-    message_contains: Vec<String>,
+    /// Moves the iCalendar output path specified by the user out of `Args`
+    ///
+    /// See `Args::take_output` for details.
+    #[must_use]
+    pub fn take_ics_output(&mut self) -> Option<PathBuf> {
+        self.ics_output.take()
+    }
 
-    /// Filters for certain commit messages. ORs if specified multiple times.
-    #[clap(long)]
-    message_equals: Vec<String>,
+    /// Moves the SQLite output path specified by the user out of `Args`
+    ///
+    /// See `Args::take_output` for details.
+    #[must_use]
+    pub fn take_sqlite_output(&mut self) -> Option<PathBuf> {
+        self.sqlite_output.take()
+    }
 
-    /// Filters for certain commit messages. ORs if specified multiple times.
-    #[clap(short = 'l', long)]
-    message_starts_with: Vec<String>,
-}
+    /// Gets the time tracker to export the detected work sessions for.
+    #[must_use]
+    pub fn tracker(&self) -> Option<crate::tracker::TrackerFormat> {
+        self.tracker
+    }
 
-impl Args {
-    /// Gets the input method specified by the user.
+    /// Creates the assignment of fields to the properties of time entries as
+    /// specified by the user.
     #[must_use]
-    pub fn input_method(&self) -> &InputMethod {
-        &self.input_method
+    pub fn tracker_mapping(&self) -> crate::tracker::Mapping {
+        crate::tracker::Mapping {
+            description: self.tracker_description,
+            project: self.tracker_project,
+            tags: if self.tracker_tags.is_empty() {
+                vec![crate::tracker::Field::Authors]
+            } else {
+                self.tracker_tags.clone()
+            },
+        }
     }
 
-    /// Gets the configured verbosity level of the program.
+    /// Moves the time tracker output path specified by the user out of `Args`
+    ///
+    /// See `Args::take_output` for details.
     #[must_use]
-    pub fn is_verbose(&self) -> bool {
-        self.is_verbose
+    pub fn take_tracker_output(&mut self) -> Option<PathBuf> {
+        self.tracker_output.take()
     }
 
-    /// Gets the maximum duration between two commits considered spent working.
+    /// Whether to list the detected work sessions.
     #[must_use]
-    pub fn duration(&self) -> u32 {
-        self.duration
+    pub fn sessions(&self) -> bool {
+        self.sessions
     }
 
-    /// Moves the output path specified by the user out of `Args`
+    /// Moves the sessions output path specified by the user out of `Args`
     ///
-    /// This method moves the specified path to the intended output file out of
-    /// this struct by calling `Option::take`. This should, hence, be called
-    /// just once but prevents an obsolete clone.
+    /// See `Args::take_output` for details.
     #[must_use]
-    pub fn take_output(&mut self) -> Option<PathBuf> {
-        self.output.take()
+    pub fn take_sessions_output(&mut self) -> Option<PathBuf> {
+        self.sessions_output.take()
+    }
+
+    /// Moves the sprint calendar path specified by the user out of `Args`
+    ///
+    /// See `Args::take_output` for details.
+    #[must_use]
+    pub fn take_sprint_calendar(&mut self) -> Option<PathBuf> {
+        self.sprint_calendar.take()
     }
 
     /// Creates a new Filter as specified by the user.
     #[must_use]
-    pub fn filter(&self) -> Filter {
+    pub fn filter(&self) -> Filter<'_> {
         Filter {
             author_contains: &self.author_contains,
             author_equals: &self.author_equals,
             commit_contains: &self.commit_contains,
             commit_equals: &self.commit_equals,
+            commit_type: &self.commit_type,
             email_contains: &self.email_contains,
             email_equals: &self.email_equals,
             file_extension: &self.file_extension,
+            language: &self.language,
+            language_override: &self.language_override,
+            merge_author_names: self.merge_author_names,
             message_contains: &self.message_contains,
             message_equals: &self.message_equals,
             message_starts_with: &self.message_starts_with,
+            scope: &self.scope,
         }
     }
 }
 
+/// The possible commands.
+#[derive(Subcommand, Clone, Debug)]
+pub enum Command {
+    /// Prints the report.
+    #[clap(flatten)]
+    Report(InputMethod),
+
+    /// Runs a query over the commits instead of printing the report.
+    Query {
+        /// The query, e.g. `group by author, month select sum(hours), count()`.
+        query: String,
+
+        /// Specifies how the program will read the Git history.
+        #[clap(subcommand)]
+        input_method: InputMethod,
+    },
+}
+
 /// The possible input methods.
-#[derive(Subcommand, Debug)]
+#[derive(Subcommand, Clone, Debug)]
 pub enum InputMethod {
     /// Reads the input from the local Git history.
     GitHistory,
@@ -290,6 +1686,37 @@ pub enum InputMethod {
 }
 
 impl InputMethod {
+    /// Guesses the name of the repository the input belongs to.
+    ///
+    /// This is the name of the Git working directory, the stem of the log
+    /// file or `stdin`.
+    pub fn repository(&self) -> String {
+        match self {
+            Self::GitHistory => {
+                let toplevel = std::process::Command::new("git")
+                    .arg("rev-parse")
+                    .arg("--show-toplevel")
+                    .output()
+                    .ok()
+                    .and_then(|process| String::from_utf8(process.stdout).ok())
+                    .map(|path| PathBuf::from(path.trim()))
+                    .filter(|path| !path.as_os_str().is_empty())
+                    .or_else(|| std::env::current_dir().ok());
+
+                toplevel
+                    .as_deref()
+                    .and_then(std::path::Path::file_name)
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "repository".to_owned())
+            }
+            Self::LogFile { log_file } => log_file
+                .file_stem()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "repository".to_owned()),
+            Self::Stdin => "stdin".to_owned(),
+        }
+    }
+
     /// Processes the configured input method.
     pub fn read(&self) -> Result<String, Box<dyn std::error::Error>> {
         match self {
@@ -324,7 +1751,7 @@ impl InputMethod {
 /// The revealed filter criteria.
 ///
 /// This data structure allows to filter the input commits by certain criteria.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, serde::Serialize)]
 pub struct Filter<'a> {
     /// A set of substrings to be contained by some authors' names.
     author_contains: &'a [String],
@@ -338,6 +1765,9 @@ pub struct Filter<'a> {
     /// A set of strings to match some commits' hashes.
     commit_equals: &'a [String],
 
+    /// A set of strings to match some commits' Conventional Commits types.
+    commit_type: &'a [String],
+
     /// A set of substrings to be contained by some authors' email addresses.
     email_contains: &'a [String],
 
@@ -347,6 +1777,16 @@ pub struct Filter<'a> {
     /// A set of file extensions to filter by.
     file_extension: &'a [String],
 
+    /// A set of languages to filter by, ignoring the case.
+    language: &'a [String],
+
+    /// The user-defined languages of some files.
+    language_override: &'a [crate::language::Override],
+
+    /// Whether identities sharing a name are considered the same author, see
+    /// `crate::authors`.
+    merge_author_names: bool,
+
     /// A set of substrings to be contained by some commits' messages.
     message_contains: &'a [String],
 
@@ -355,59 +1795,144 @@ pub struct Filter<'a> {
 
     /// A set of strings to introduce some commits' messages.
     message_starts_with: &'a [String],
+
+    /// A set of strings to match some commits' Conventional Commits scopes.
+    scope: &'a [String],
 }
 
 impl Filter<'_> {
-    /// Whether the author's email address matches the expectations.
-    fn check_author_email(&self, email: &str) -> bool {
-        let contains =
-            self.email_contains.is_empty() || self.email_contains.iter().any(|e| email.contains(e));
-        let equals = self.email_equals.is_empty() || self.email_equals.iter().any(|e| e == email);
-
-        equals && contains
-    }
-
-    /// Whether the author's name matches the expectations.
-    fn check_author_name(&self, name: &str) -> bool {
-        let contains = self.author_contains.is_empty()
-            || self.author_contains.iter().any(|n| name.contains(n));
-        let equals = self.author_equals.is_empty() || self.author_equals.iter().any(|n| n == name);
-
-        equals && contains
-    }
-
-    /// Whether the commit meta data matches the expectations.
-    fn check_commit(&self, commit: &str) -> bool {
-        let contains = self.commit_contains.is_empty()
-            || self.commit_contains.iter().any(|c| commit.contains(c));
-        let equals =
-            self.commit_equals.is_empty() || self.commit_equals.iter().any(|c| c == commit);
-
-        equals && contains
+    /// Checks the given `commit` against every configured filter clause.
+    ///
+    /// Clauses without any configured values are omitted since they accept
+    /// every commit.
+    pub fn clauses<'a>(&'a self, commit: &'a crate::Commit) -> Vec<Clause<'a>> {
+        let name = commit.author().name();
+        let email = commit.author().email();
+        let hash = commit.commit();
+        let message = commit.message();
+        let header = commit.conventional();
+        let kind = header
+            .map(crate::conventional::Header::kind)
+            .unwrap_or_default();
+        let scope = header.and_then(|h| h.scope()).unwrap_or_default();
+        let checks = [
+            (
+                "--author-contains",
+                self.author_contains,
+                name,
+                Comparison::Contains,
+            ),
+            (
+                "--author-equals",
+                self.author_equals,
+                name,
+                Comparison::Equals,
+            ),
+            (
+                "--email-contains",
+                self.email_contains,
+                email,
+                Comparison::Contains,
+            ),
+            (
+                "--email-equals",
+                self.email_equals,
+                email,
+                Comparison::Equals,
+            ),
+            (
+                "--commit-contains",
+                self.commit_contains,
+                hash,
+                Comparison::Contains,
+            ),
+            (
+                "--commit-equals",
+                self.commit_equals,
+                hash,
+                Comparison::Equals,
+            ),
+            (
+                "--message-contains",
+                self.message_contains,
+                message,
+                Comparison::Contains,
+            ),
+            (
+                "--message-equals",
+                self.message_equals,
+                message,
+                Comparison::Equals,
+            ),
+            (
+                "--message-starts-with",
+                self.message_starts_with,
+                message,
+                Comparison::StartsWith,
+            ),
+            (
+                "--type",
+                self.commit_type,
+                kind,
+                Comparison::EqualsIgnoringCase,
+            ),
+            ("--scope", self.scope, scope, Comparison::EqualsIgnoringCase),
+        ];
+
+        checks
+            .into_iter()
+            .filter(|(_, values, _, _)| !values.is_empty())
+            .map(|(option, values, property, comparison)| Clause {
+                accepted: values.iter().any(|v| comparison.test(property, v)),
+                option,
+                property,
+                values,
+            })
+            .collect()
     }
 
     /// Whether the LOC diff matches the expectations.
     pub fn check_loc(&self, loc: &&crate::LocDiff) -> bool {
-        self.file_extension.is_empty()
-            || self
+        self.rejecting_loc_option(loc).is_none()
+    }
+
+    /// The option rejecting the LOC diff, if any.
+    ///
+    /// The file extensions are checked before the languages.
+    pub fn rejecting_loc_option(&self, loc: &crate::LocDiff) -> Option<&'static str> {
+        if !self.file_extension.is_empty()
+            && !self
                 .file_extension
                 .iter()
                 .any(|ext| loc.file().ends_with(&format!(".{}", ext)))
-    }
+        {
+            return Some("--file-extension");
+        }
 
-    /// Whether the message matches the expectations.
-    fn check_message(&self, message: &str) -> bool {
-        let contains = self.message_contains.is_empty()
-            || self.message_contains.iter().any(|m| message.contains(m));
-        let equals =
-            self.message_equals.is_empty() || self.message_equals.iter().any(|m| m == message);
-        let starts_with = self.message_starts_with.is_empty()
-            || self
-                .message_starts_with
+        if !self.language.is_empty() {
+            let language = self.language(loc);
+
+            if !self
+                .language
                 .iter()
-                .any(|m| message.starts_with(m));
+                .any(|l| l.eq_ignore_ascii_case(language))
+            {
+                return Some("--language");
+            }
+        }
+
+        None
+    }
+
+    /// The getter method for the field `merge_author_names` of the
+    /// corresponding struct.
+    pub fn merge_author_names(&self) -> bool {
+        self.merge_author_names
+    }
 
-        equals && contains && starts_with
+    /// The language of the changed file, respecting the user's overrides.
+    pub fn language(&self, loc: &crate::LocDiff) -> &str {
+        crate::language::classify(&loc.new_path(), self.language_override)
     }
 
     /// An abbreviation for the filter checks.
@@ -415,10 +1940,84 @@ impl Filter<'_> {
     /// This function checks whether the given `commit` matches the
     /// expectations defined in this `filter`.
     pub fn matches(&self, commit: &crate::Commit) -> bool {
-        self.check_author_name(commit.author().name())
-            && self.check_author_email(commit.author().email())
-            && self.check_commit(commit.commit())
-            && self.check_message(commit.message())
+        self.clauses(commit).iter().all(Clause::accepted)
+    }
+}
+
+/// The ways a filter clause compares a commit's property with its values.
+#[derive(Clone, Copy, Debug)]
+enum Comparison {
+    /// The property needs to contain the value.
+    Contains,
+
+    /// The property needs to equal the value.
+    Equals,
+
+    /// The property needs to equal the value, ignoring the case.
+    EqualsIgnoringCase,
+
+    /// The property needs to start with the value.
+    StartsWith,
+}
+
+impl Comparison {
+    /// Whether the given property fits the given value.
+    fn test(self, property: &str, value: &str) -> bool {
+        match self {
+            Self::Contains => property.contains(value),
+            Self::Equals => property == value,
+            Self::EqualsIgnoringCase => property.to_lowercase() == value.to_lowercase(),
+            Self::StartsWith => property.starts_with(value),
+        }
+    }
+}
+
+/// The verdict of a single filter criterion.
+///
+/// A clause compares a certain property of a commit, such as the author's
+/// name, with the values given to one of the filter options. It accepts the
+/// commit if any of these values fits.
+#[derive(Debug)]
+pub struct Clause<'a> {
+    /// Whether the commit fits this clause.
+    accepted: bool,
+
+    /// The command line option which configured this clause.
+    option: &'static str,
+
+    /// The commit's property this clause checks.
+    property: &'a str,
+
+    /// The values configured for this clause.
+    values: &'a [String],
+}
+
+impl Clause<'_> {
+    /// The getter method for the field `accepted` of the corresponding struct.
+    pub fn accepted(&self) -> bool {
+        self.accepted
+    }
+
+    /// The getter method for the field `option` of the corresponding struct.
+    pub fn option(&self) -> &'static str {
+        self.option
+    }
+}
+
+impl std::fmt::Display for Clause<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} by {} {:?} for {:?}",
+            if self.accepted {
+                "accepted"
+            } else {
+                "rejected"
+            },
+            self.option,
+            self.values,
+            self.property
+        )
     }
 }
 
@@ -476,6 +2075,17 @@ pub enum AuthorParseError {
     NameFailed,
 }
 
+impl std::fmt::Display for AuthorParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EmailFailed => write!(f, "the email address is not wrapped in `<>`"),
+            Self::NameFailed => write!(f, "the name is not followed by an email address"),
+        }
+    }
+}
+
+impl std::error::Error for AuthorParseError {}
+
 /// The commit information.
 #[derive(Debug)]
 pub struct Commit {
@@ -485,6 +2095,9 @@ pub struct Commit {
     /// The commit's hash.
     commit: String,
 
+    /// The Conventional Commits header of the message, if any.
+    conventional: Option<crate::conventional::Header>,
+
     /// The commit's date.
     date: chrono::DateTime<chrono::FixedOffset>,
 
@@ -492,10 +2105,6 @@ pub struct Commit {
     locs: Vec<crate::LocDiff>,
 
     /// The merge information.
-    ///
-    /// This field is currently unused, if this changes, you can delete this
-    /// `#[allow(dead_code)]` tag.
-    #[allow(dead_code)]
     merge: Option<String>,
 
     /// The commit's description.
@@ -513,25 +2122,99 @@ impl Commit {
         &self.commit
     }
 
+    /// The getter method for the field `conventional` of the corresponding
+    /// struct.
+    pub fn conventional(&self) -> Option<&crate::conventional::Header> {
+        self.conventional.as_ref()
+    }
+
     /// The getter method for the field `date` of the corresponding struct.
     pub fn date(&self) -> &chrono::DateTime<chrono::FixedOffset> {
         &self.date
     }
 
-    /// The getter method for the field `loc` of the corresponding struct.
+    /// The number of binary files matching the given filter.
+    ///
+    /// Git does not count the lines of binary files, hence, they do not
+    /// contribute to the LOC diff.
+    pub fn binary_files(&self, filter: &crate::Filter) -> usize {
+        self.locs
+            .iter()
+            .filter(|l| filter.check_loc(l))
+            .filter(|l| l.is_binary())
+            .count()
+    }
+
+    /// The net LOC diff of the files matching the given filter.
+    ///
+    /// See `Commit::loc_stats` for the inserted and deleted lines.
     pub fn loc(&self, filter: &crate::Filter) -> i64 {
+        self.loc_stats(filter).net()
+    }
+
+    /// The inserted and deleted lines of the files matching the given filter.
+    pub fn loc_stats(&self, filter: &crate::Filter) -> crate::loc::LocStats {
         self.locs
             .iter()
             .filter(|l| filter.check_loc(l))
-            .map(|l| l.loc())
+            .map(|l| l.loc_stats())
             .sum()
     }
 
+    /// The getter method for the field `locs` of the corresponding struct.
+    pub fn locs(&self) -> &[crate::LocDiff] {
+        &self.locs
+    }
+
+    /// The getter method for the field `merge` of the corresponding struct.
+    pub fn merge(&self) -> Option<&str> {
+        self.merge.as_deref()
+    }
+
     /// The getter method for the field `message` of the corresponding struct.
     pub fn message(&self) -> &str {
         &self.message
     }
 
+    /// The first line of the commit's description.
+    pub fn subject(&self) -> &str {
+        self.message.lines().next().unwrap_or_default()
+    }
+
+    /// Constructs all commits from the output of `git log --numstat`.
+    ///
+    /// Commits which cannot be parsed are skipped and reported as warnings.
+    /// The commits are returned in the order of the log, i.e. the newest
+    /// commit comes first.
+    pub fn parse_log(log: &str) -> (Vec<Self>, Vec<ParseWarning>) {
+        let mut commits = vec![];
+        let mut warnings = vec![];
+        let mut remainder = log;
+
+        while !remainder.is_empty() {
+            match Self::parse(remainder) {
+                Ok((commit, rest)) => {
+                    commits.push(commit);
+                    remainder = rest;
+                }
+                Err(error) => {
+                    let offset = log.len() - remainder.len();
+
+                    warnings.push(ParseWarning {
+                        error,
+                        line: log[..offset].matches('\n').count() + 1,
+                    });
+                    remainder = match remainder.find("\ncommit ") {
+                        Some(index) => &remainder[index + 1..],
+                        None => "",
+                    };
+                }
+            }
+        }
+
+        (commits, warnings)
+    }
+
     /// Constructs a new instance from the raw input data.
     pub fn parse(commit: &str) -> Result<(Self, &str), CommitParseError> {
         let (commit, remainder) = commit
@@ -554,39 +2237,25 @@ impl Commit {
             .ok_or(CommitParseError::AuthorMissing)?
             .split_once('\n')
             .ok_or(CommitParseError::AuthorMissing)?;
-        let (date, mut remainder) = remainder
+        let (date, remainder) = remainder
             .strip_prefix("Date:   ")
             .ok_or(CommitParseError::DateMissing)?
             .split_once('\n')
             .ok_or(CommitParseError::DateMissing)?;
 
-        let mut message = String::new();
-        let mut remainder_result = remainder;
-        if let Some(it) = remainder.strip_prefix('\n') {
-            remainder = it;
+        // Every line of the message is indented by four spaces, the message is
+        // surrounded by empty lines.
+        let mut lines = vec![];
+        let mut remainder_result = remainder.strip_prefix('\n').unwrap_or(remainder);
+        while let Some(line) = remainder_result.strip_prefix("    ") {
+            let (line, rest) = line.split_once('\n').unwrap_or((line, ""));
+            lines.push(line);
+            remainder_result = rest;
         }
-        let mut space_count = 0;
-        let mut increase_space_count = true;
-        for (index, char) in remainder.char_indices() {
-            if increase_space_count {
-                if char == ' ' {
-                    space_count += 1;
-                } else {
-                    increase_space_count = false;
-                }
-            } else if space_count == 4 && char == '\n' {
-                increase_space_count = true;
-                space_count = 0;
-            } else if space_count < 4 {
-                break;
-            }
-            // This removes the char from remainder, before adding it to the
-            // message.
-            remainder_result = &remainder[index + char.len_utf8()..];
-            if !increase_space_count {
-                message.push(char);
-            }
+        if let Some(rest) = remainder_result.strip_prefix('\n') {
+            remainder_result = rest;
         }
+        let message = lines.join("\n");
         let message = message.trim();
 
         let mut locs = vec![];
@@ -616,6 +2285,7 @@ impl Commit {
         Ok((
             Self {
                 commit: commit.into(),
+                conventional: crate::conventional::Header::parse(message),
                 merge,
                 author: crate::Author::parse(author).map_err(CommitParseError::AuthorFailed)?,
                 date: chrono::DateTime::parse_from_str(date, "%a %b %e %T %Y %z")
@@ -656,6 +2326,51 @@ pub enum CommitParseError {
     Unknown,
 }
 
+impl std::fmt::Display for CommitParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AuthorFailed(error) => write!(f, "invalid author, {}", error),
+            Self::AuthorMissing => write!(f, "the author is missing"),
+            Self::CommitMissing => write!(f, "the commit hash is missing"),
+            Self::DateFailed(error) => write!(f, "invalid date, {}", error),
+            Self::DateMissing => write!(f, "the date is missing"),
+            Self::LocFailed(error) => write!(f, "invalid LOC diff, {}", error),
+            Self::LocSyntaxError => write!(f, "the LOC diff is malformatted"),
+            Self::Unknown => write!(f, "unknown reason"),
+        }
+    }
+}
+
+impl std::error::Error for CommitParseError {}
+
+/// A commit which could not be parsed and was skipped.
+#[derive(Debug)]
+pub struct ParseWarning {
+    /// The reason why the commit could not be parsed.
+    error: CommitParseError,
+
+    /// The line of the input the skipped commit starts at.
+    line: usize,
+}
+
+impl ParseWarning {
+    /// The getter method for the field `error` of the corresponding struct.
+    pub fn error(&self) -> &CommitParseError {
+        &self.error
+    }
+
+    /// The getter method for the field `line` of the corresponding struct.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+impl std::fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
 /// The LOC diff a certain commit introduces.
 ///
 /// LOC is the abbreviation for the number of **l**ines **o**f **c**ode a
@@ -684,20 +2399,84 @@ pub struct LocDiff {
 }
 
 impl LocDiff {
+    /// The getter method for the field `added` of the corresponding struct.
+    pub fn added(&self) -> Option<u32> {
+        self.added
+    }
+
+    /// The getter method for the field `removed` of the corresponding struct.
+    pub fn removed(&self) -> Option<u32> {
+        self.removed
+    }
+
     /// The getter method for the field `file` of the corresponding struct.
     pub fn file(&self) -> &str {
         &self.file
     }
 
+    /// The path of the affected file before the commit.
+    ///
+    /// This differs from `LocDiff::new_path` only for renamed files.
+    pub fn old_path(&self) -> String {
+        self.rename()
+            .map_or_else(|| self.file.clone(), |(old, _)| old)
+    }
+
+    /// The path of the affected file after the commit.
+    ///
+    /// Git abbreviates renamed files as `old => new` or, if both paths share a
+    /// prefix or suffix, as `src/{old.rs => new.rs}`. This method resolves
+    /// these abbreviations.
+    pub fn new_path(&self) -> String {
+        self.rename()
+            .map_or_else(|| self.file.clone(), |(_, new)| new)
+    }
+
+    /// Splits the abbreviation of a renamed file into the old and new path.
+    fn rename(&self) -> Option<(String, String)> {
+        if let (Some(open), Some(close)) = (self.file.find('{'), self.file.rfind('}')) {
+            if let Some((old, new)) = self.file[open + 1..close].split_once(" => ") {
+                let (prefix, suffix) = (&self.file[..open], &self.file[close + 1..]);
+                let join = |middle: &str| {
+                    format!("{}{}{}", prefix, middle, suffix)
+                        .replace("//", "/")
+                        .trim_start_matches('/')
+                        .to_owned()
+                };
+
+                return Some((join(old), join(new)));
+            }
+        }
+
+        self.file
+            .split_once(" => ")
+            .map(|(old, new)| (old.to_owned(), new.to_owned()))
+    }
+
+    /// Whether the affected file is a binary file.
+    ///
+    /// Git does not count the lines of binary files.
+    pub fn is_binary(&self) -> bool {
+        self.added.is_none() && self.removed.is_none()
+    }
+
     /// Calculates the LOC diff.
     pub fn loc(&self) -> i64 {
-        if self.added.is_none() && self.removed.is_none() {
+        if self.is_binary() {
             0
         } else {
             self.added.unwrap() as i64 - self.removed.unwrap() as i64
         }
     }
 
+    /// The inserted and deleted lines, which are zero for binary files.
+    pub fn loc_stats(&self) -> crate::loc::LocStats {
+        crate::loc::LocStats::new(
+            self.added.unwrap_or_default().into(),
+            self.removed.unwrap_or_default().into(),
+        )
+    }
+
     /// Extracts the LOC diff information from the given line.
     pub fn parse(loc: &str) -> Result<Self, LocParseError> {
         let (added, remainder) = loc
@@ -738,3 +2517,110 @@ pub enum LocParseError {
     /// The tab character between the deletions and file name is missing.
     SecondTabulatorMissing,
 }
+
+impl std::fmt::Display for LocParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AddedParseError(error) => write!(f, "invalid number of insertions, {}", error),
+            Self::FirstTabulatorMissing => write!(f, "the first tabulator is missing"),
+            Self::RemovedParseError(error) => write!(f, "invalid number of deletions, {}", error),
+            Self::SecondTabulatorMissing => write!(f, "the second tabulator is missing"),
+        }
+    }
+}
+
+impl std::error::Error for LocParseError {}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::{Args, LocDiff};
+
+    fn paths(line: &str) -> (String, String) {
+        let loc = LocDiff::parse(line).unwrap();
+
+        (loc.old_path(), loc.new_path())
+    }
+
+    #[test]
+    fn loc_diff_resolves_renames() {
+        let pair = |old: &str, new: &str| (old.to_owned(), new.to_owned());
+
+        assert_eq!(
+            paths("1\t2\tsrc/main.rs"),
+            pair("src/main.rs", "src/main.rs")
+        );
+        assert_eq!(paths("0\t0\ta.txt => b.txt"), pair("a.txt", "b.txt"));
+        assert_eq!(
+            paths("0\t0\tsrc/{main.rs => lib.rs}"),
+            pair("src/main.rs", "src/lib.rs")
+        );
+        assert_eq!(
+            paths("0\t0\tsrc/{ => nested}/lib.rs"),
+            pair("src/lib.rs", "src/nested/lib.rs")
+        );
+        assert_eq!(
+            paths("0\t0\t{old => new}/lib.rs"),
+            pair("old/lib.rs", "new/lib.rs")
+        );
+    }
+
+    #[test]
+    fn loc_diff_counts_binary_files() {
+        let loc = LocDiff::parse("-\t-\tlogo.png").unwrap();
+
+        assert!(loc.is_binary());
+        assert_eq!(loc.loc(), 0);
+        assert!(LocDiff::parse("1\tlogo.png").is_err());
+        assert!(LocDiff::parse("x\t1\tlogo.png").is_err());
+    }
+
+    #[test]
+    fn filter_compares_types_and_scopes_ignoring_the_case() {
+        let (commits, _) = crate::Commit::parse_log(
+            "commit 1111111111111111111111111111111111111111
+Author: Alice <alice@example.com>
+Date:   Wed Jun 1 09:00:00 2022 +0000
+
+    Feat(Parser): support arrays
+",
+        );
+        let accepted = |options: &[&str]| {
+            let args = Args::parse_from(
+                ["commit-analyzer"]
+                    .iter()
+                    .chain(options)
+                    .chain(&["log-file", "log"]),
+            );
+            let filter = args.filter();
+            let clauses = filter.clauses(&commits[0]);
+
+            clauses.iter().all(super::Clause::accepted)
+        };
+
+        assert!(accepted(&["--type", "FEAT", "--scope", "parser"]));
+        assert!(!accepted(&["--type", "fix"]));
+    }
+
+    #[test]
+    fn filter_reports_the_option_rejecting_a_file() {
+        let rejecting = |options: &[&str], file: &str| {
+            let args = Args::parse_from(
+                ["commit-analyzer"]
+                    .iter()
+                    .chain(options)
+                    .chain(&["log-file", "log"]),
+            );
+            let loc = LocDiff::parse(&format!("1\t0\t{}", file)).unwrap();
+
+            args.filter().rejecting_loc_option(&loc)
+        };
+        let options = ["--file-extension", "rs", "--file-extension", "md"];
+        let options = [&options[..], &["--language", "Rust"]].concat();
+
+        assert_eq!(rejecting(&options, "src/lib.rs"), None);
+        assert_eq!(rejecting(&options, "README.md"), Some("--language"));
+        assert_eq!(rejecting(&options, "ci.yml"), Some("--file-extension"));
+    }
+}
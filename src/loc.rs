@@ -0,0 +1,117 @@
+//! The counting of inserted and deleted lines.
+//!
+//! The net LOC diff alone hides large refactorings, since a change which adds
+//! and removes the same number of lines sums up to zero. Hence, the inserted
+//! and deleted lines are tracked separately and combined on demand.
+
+/// The inserted and deleted lines of one or more changes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LocStats {
+    /// The number of inserted lines.
+    added: u64,
+
+    /// The number of deleted lines.
+    removed: u64,
+}
+
+impl LocStats {
+    /// Creates the statistics for the given number of inserted and deleted
+    /// lines.
+    pub fn new(added: u64, removed: u64) -> Self {
+        Self { added, removed }
+    }
+
+    /// The getter method for the field `added` of the corresponding struct.
+    pub fn added(&self) -> u64 {
+        self.added
+    }
+
+    /// The gross churn, i.e. the inserted plus the deleted lines.
+    pub fn churn(&self) -> u64 {
+        self.added + self.removed
+    }
+
+    /// The value of the given metric.
+    pub fn get(&self, metric: LocMetric) -> i64 {
+        match metric {
+            LocMetric::Net => self.net(),
+            LocMetric::Churn => self.churn() as i64,
+            LocMetric::Added => self.added as i64,
+            LocMetric::Removed => self.removed as i64,
+        }
+    }
+
+    /// The net LOC diff, i.e. the inserted minus the deleted lines.
+    pub fn net(&self) -> i64 {
+        self.added as i64 - self.removed as i64
+    }
+
+    /// The getter method for the field `removed` of the corresponding struct.
+    pub fn removed(&self) -> u64 {
+        self.removed
+    }
+}
+
+impl std::ops::Add for LocStats {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            added: self.added + other.added,
+            removed: self.removed + other.removed,
+        }
+    }
+}
+
+impl std::ops::AddAssign for LocStats {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl std::iter::Sum for LocStats {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |sum, loc| sum + loc)
+    }
+}
+
+impl serde::Serialize for LocStats {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("LocStats", 4)?;
+        state.serialize_field("added", &self.added)?;
+        state.serialize_field("removed", &self.removed)?;
+        state.serialize_field("net", &self.net())?;
+        state.serialize_field("churn", &self.churn())?;
+        state.end()
+    }
+}
+
+/// The possible ways to sum up inserted and deleted lines to a single number.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LocMetric {
+    /// The inserted minus the deleted lines.
+    Net,
+
+    /// The inserted plus the deleted lines.
+    Churn,
+
+    /// The inserted lines.
+    Added,
+
+    /// The deleted lines.
+    Removed,
+}
+
+impl std::fmt::Display for LocMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Net => write!(f, "net"),
+            Self::Churn => write!(f, "churn"),
+            Self::Added => write!(f, "added"),
+            Self::Removed => write!(f, "removed"),
+        }
+    }
+}
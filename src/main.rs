@@ -1,9 +1,37 @@
-use std::{collections::HashMap, io::Write, ops::AddAssign};
+use std::collections::{BTreeMap, BTreeSet};
 
-use clap::Parser;
+use commit_analyzer::{
+    activity::Activity,
+    attribution::TimeShare,
+    authors::AuthorSummary,
+    chart, conventional,
+    coupling::{self, Coupling},
+    csv,
+    explain::Explanation,
+    group::Grouping,
+    hotspots::Hotspots,
+    ical, language,
+    ownership::Ownership,
+    period::{Calendar, Period},
+    punchcard::PunchCard,
+    query::Query,
+    report::{Format, Report},
+    session::{format_duration, hours, Session, SUBJECT_COUNT},
+    sqlite,
+    table::{Alignment, Table},
+    tickets::Ticket,
+};
 
 fn main() -> sysexits::ExitCode {
-    let mut args = commit_analyzer::Args::parse();
+    let mut args = commit_analyzer::Args::parse_checked();
+    let query = match args.query().map(str::parse::<Query>) {
+        Some(Ok(query)) => Some(query),
+        Some(Err(err)) => {
+            eprintln!("The query is invalid, {}.", err);
+            return sysexits::ExitCode::Usage;
+        }
+        None => None,
+    };
 
     let commits = match args.input_method().read() {
         Ok(string) => string,
@@ -22,77 +50,574 @@ fn main() -> sysexits::ExitCode {
             }
         },
     };
-    let mut commits = commits.as_str();
-    let mut parsed_commits = vec![];
-    while !commits.is_empty() {
-        let result = commit_analyzer::Commit::parse(commits);
-        match result {
-            Ok((commit, remainder)) => {
-                commits = remainder;
-                parsed_commits.push(commit);
-            }
-            Err(err) => {
-                dbg!(err);
-                break;
+    let (parsed_commits, warnings) = commit_analyzer::Commit::parse_log(&commits);
+    for warning in &warnings {
+        eprintln!("Skipping a malformed commit, {}.", warning);
+    }
+    let output = args.take_output();
+    let sessions_output = args.take_sessions_output();
+    let punch_card_output = args.take_punch_card_output();
+    let svg_output = args.take_svg_output();
+    let ics_output = args.take_ics_output();
+    let tracker_output = args.take_tracker_output();
+    let sqlite_output = args.take_sqlite_output();
+    let hotspots_output = args.take_hotspots_output();
+    let tickets_output = args.take_tickets_output();
+    let coupling_dot = args.take_coupling_dot();
+    let calendar = match args.take_sprint_calendar() {
+        Some(path) => match std::fs::read_to_string(&path) {
+            Ok(text) => match Period::parse_sprints(&text) {
+                Ok(sprints) => Calendar::Sprints(sprints),
+                Err(err) => {
+                    eprintln!(
+                        "The sprint calendar '{}' is invalid, {}.",
+                        path.display(),
+                        err
+                    );
+                    return sysexits::ExitCode::DataErr;
+                }
+            },
+            Err(_) => {
+                eprintln!(
+                    "The sprint calendar '{}' could not be read.",
+                    path.display()
+                );
+                return sysexits::ExitCode::NoInput;
             }
+        },
+        None => args.calendar(),
+    };
+    let columns = args.columns(&calendar);
+    if !args.per_commit() {
+        if let Some(column) = columns.iter().find(|c| c.is_commit_only()) {
+            eprintln!(
+                "The column '{}' is only available with `--per-commit`.",
+                column
+            );
+            return sysexits::ExitCode::Usage;
         }
     }
-    let mut last_time = None;
-    let mut duration = chrono::Duration::zero();
+    let timezone = args.timezone();
     let filter = args.filter();
+    let is_text = args.format() == Format::Text;
+    if args.explain() && is_text {
+        for explanation in Explanation::explain(
+            parsed_commits.iter().rev(),
+            &filter,
+            args.duration(),
+            timezone,
+        ) {
+            println!("{}", explanation);
+        }
+    }
     let mut commit_count = 0;
-    let mut commits_per_day = HashMap::new();
-    let mut loc_per_day = HashMap::new();
-    for commit in parsed_commits.into_iter().rev() {
-        if filter.matches(&commit) {
+    let mut matching_commits = vec![];
+    for commit in parsed_commits.iter().rev() {
+        if filter.matches(commit) {
             commit_count += 1;
-            commits_per_day
-                .entry(commit.date().date())
-                .or_insert(0)
-                .add_assign(1);
-            loc_per_day
-                .entry(commit.date().date())
-                .or_insert(0)
-                .add_assign(commit.loc(&filter));
-            if let Some(last_time) = last_time {
-                let diff: chrono::Duration = *commit.date() - last_time;
-                if diff.num_hours() <= args.duration() as i64 {
-                    duration = duration + diff;
+            matching_commits.push(commit);
+            if args.is_verbose() && is_text {
+                println!("{:#?}", commit);
+            }
+        }
+    }
+    let sessions = Session::detect(matching_commits, args.duration());
+    let duration = sessions
+        .iter()
+        .fold(chrono::Duration::zero(), |sum, s| sum + s.duration());
+
+    if let Some(query) = query {
+        let result = query.run(
+            &sessions,
+            &filter,
+            timezone,
+            args.time_attribution(),
+            &args.repository(),
+        );
+        match args.format() {
+            Format::Text => print!("{}", result.to_table()),
+            Format::Json => match serde_json::to_string_pretty(&result.to_json()) {
+                Ok(json) => println!("{}", json),
+                Err(_) => return sysexits::ExitCode::Software,
+            },
+            format => {
+                eprintln!("Queries cannot be printed as {:?}.", format);
+                return sysexits::ExitCode::Usage;
+            }
+        }
+        if let Some(path) = output {
+            let file = match std::fs::File::create(path) {
+                Ok(file) => file,
+                Err(_) => return sysexits::ExitCode::CantCreat,
+            };
+            let mut writer = csv::Writer::new(std::io::BufWriter::new(file), args.delimiter());
+            match csv::write_query(&mut writer, &result) {
+                Ok(something) => something,
+                Err(_) => return sysexits::ExitCode::IoErr,
+            };
+        }
+        return sysexits::ExitCode::Ok;
+    }
+
+    match args.format() {
+        Format::Text => {
+            println!("Estimated time was {}h", duration.num_hours());
+            println!("Found {} commits overall", commit_count);
+            println!(
+                "Counted {} LOC ({})",
+                sessions
+                    .iter()
+                    .map(|s| s.loc(&filter).get(args.loc_metric()))
+                    .sum::<i64>(),
+                args.loc_metric()
+            );
+
+            if args.authors() {
+                let loc = format!("Loc ({})", args.loc_metric());
+                let mut table = Table::new(&[
+                    ("Author", Alignment::Left),
+                    ("Hours", Alignment::Right),
+                    ("Share", Alignment::Right),
+                    ("Sessions", Alignment::Right),
+                    ("Commits", Alignment::Right),
+                    ("Active Days", Alignment::Right),
+                    ("Added", Alignment::Right),
+                    ("Removed", Alignment::Right),
+                    (&loc, Alignment::Right),
+                    ("First Commit", Alignment::Left),
+                    ("Last Commit", Alignment::Left),
+                ]);
+                let mut authors = AuthorSummary::summarise(
+                    &sessions,
+                    &filter,
+                    timezone,
+                    args.sort_by(),
+                    args.loc_metric(),
+                );
+                if let Some(top) = args.top() {
+                    authors.truncate(top);
+                }
+                for author in &authors {
+                    table.push(vec![
+                        author.name().to_owned(),
+                        format!("{:.2}", author.hours()),
+                        format!("{:.1}%", author.share() * 100.0),
+                        author.sessions().to_string(),
+                        author.commits().to_string(),
+                        author.active_days().to_string(),
+                        author.loc().added().to_string(),
+                        author.loc().removed().to_string(),
+                        author.loc().get(args.loc_metric()).to_string(),
+                        author.first_commit().format("%Y-%m-%d %H:%M").to_string(),
+                        author.last_commit().format("%Y-%m-%d %H:%M").to_string(),
+                    ]);
                 }
+                println!();
+                print!("{}", table);
             }
-            last_time = Some(*commit.date());
-            if args.is_verbose() {
-                println!("{:#?}", commit);
+
+            if let Some(level) = args.time_by() {
+                let mut shares = TimeShare::attribute(
+                    &sessions,
+                    &filter,
+                    timezone,
+                    args.time_attribution(),
+                    level,
+                    args.time_depth(),
+                );
+                if let Some(top) = args.top() {
+                    shares.truncate(top);
+                }
+                println!();
+                print!("{}", TimeShare::to_table(&shares, level));
+            }
+
+            if args.tickets() {
+                let mut tickets = Ticket::collect(
+                    &sessions,
+                    &filter,
+                    timezone,
+                    args.time_attribution(),
+                    &args.ticket_patterns(),
+                );
+                if let Some(top) = args.top() {
+                    tickets.truncate(top);
+                }
+                println!();
+                print!("{}", Ticket::to_table(&tickets));
+            }
+
+            if args.types() {
+                let types = conventional::breakdown(
+                    &sessions,
+                    &filter,
+                    timezone,
+                    args.time_attribution(),
+                    &args.repository(),
+                );
+                println!();
+                print!("{}", types.to_table());
+            }
+
+            if args.languages() {
+                let languages = language::breakdown(
+                    &sessions,
+                    &filter,
+                    timezone,
+                    args.time_attribution(),
+                    &args.repository(),
+                );
+                println!();
+                print!("{}", languages.to_table());
+            }
+
+            if args.hotspots() {
+                let mut hotspots = Hotspots::new(
+                    &sessions,
+                    &filter,
+                    timezone,
+                    args.hotspot_depth(),
+                    args.hotspot_sort_by(),
+                );
+                if let Some(top) = args.top() {
+                    hotspots.truncate(top);
+                }
+                for table in hotspots.to_tables() {
+                    println!();
+                    print!("{}", table);
+                }
+            }
+
+            if args.ownership() {
+                let mut ownership =
+                    Ownership::new(&sessions, &filter, timezone, args.ownership_options());
+                if let Some(top) = args.top() {
+                    ownership.truncate(top);
+                }
+                for table in ownership.to_tables() {
+                    println!();
+                    print!("{}", table);
+                }
+            }
+
+            if let Some(level) = args.coupling() {
+                let mut pairs = Coupling::analyse(
+                    &sessions,
+                    &filter,
+                    level,
+                    args.coupling_depth(),
+                    args.coupling_thresholds(),
+                );
+                if let Some(top) = args.top() {
+                    pairs.truncate(top);
+                }
+                println!();
+                print!("{}", Coupling::to_table(&pairs));
+            }
+
+            if !args.group_by().is_empty() {
+                let grouping = Grouping::new(
+                    args.group_by(),
+                    &args.group_metrics(),
+                    &sessions,
+                    &filter,
+                    timezone,
+                    args.time_attribution(),
+                    &args.repository(),
+                );
+                println!();
+                print!("{}", grouping.to_table());
+            }
+
+            if args.sessions() {
+                let loc = format!("Loc ({})", args.loc_metric());
+                let mut table = Table::new(&[
+                    ("Start", Alignment::Left),
+                    ("End", Alignment::Left),
+                    ("Duration", Alignment::Right),
+                    ("Authors", Alignment::Left),
+                    ("Commits", Alignment::Right),
+                    (&loc, Alignment::Right),
+                    ("Subjects", Alignment::Left),
+                ]);
+                for session in &sessions {
+                    let mut subjects = session.subjects().join("; ");
+                    if session.commits().len() > SUBJECT_COUNT {
+                        subjects.push_str("; ...");
+                    }
+                    table.push(vec![
+                        timezone
+                            .convert(session.start())
+                            .format("%Y-%m-%d %H:%M")
+                            .to_string(),
+                        timezone
+                            .convert(session.end())
+                            .format("%Y-%m-%d %H:%M")
+                            .to_string(),
+                        format_duration(session.duration()),
+                        session.authors().join(", "),
+                        session.commits().len().to_string(),
+                        session.loc(&filter).get(args.loc_metric()).to_string(),
+                        subjects,
+                    ]);
+                }
+                println!();
+                print!("{}", table);
+            }
+
+            if let Some(value) = args.punch_card() {
+                let hours: Vec<String> = (0..24).map(|h| h.to_string()).collect();
+                let mut header = vec![("", Alignment::Left)];
+                header.extend(hours.iter().map(|h| (h.as_str(), Alignment::Right)));
+                let mut table = Table::new(&header);
+                for weekday in PunchCard::new(&sessions, timezone, args.week_start()).weekdays() {
+                    let mut row = vec![weekday.weekday().to_string()];
+                    row.extend(weekday.values(value).iter().map(|v| {
+                        if *v > 0.0 {
+                            format!("{:.0}", v)
+                        } else {
+                            ".".to_owned()
+                        }
+                    }));
+                    table.push(row);
+                }
+                println!();
+                print!("{}", table);
+            }
+
+            if !args.charts().is_empty() {
+                let style = chart::Style::detect();
+                let days = Activity::per_day(&sessions, &filter, timezone);
+                let hours_per_day: BTreeMap<_, _> = days
+                    .iter()
+                    .map(|(date, activity)| (*date, hours(activity.work())))
+                    .collect();
+
+                for kind in args.charts() {
+                    println!();
+                    match kind {
+                        chart::Chart::Sparkline => {
+                            println!("Hours per day");
+                            println!("{}", chart::sparkline(&hours_per_day, style));
+                        }
+                        chart::Chart::Bars => {
+                            let points: Vec<_> = calendar
+                                .aggregate(&days)
+                                .into_iter()
+                                .map(|(period, activity)| {
+                                    (period.name().to_owned(), hours(activity.work()))
+                                })
+                                .collect();
+
+                            println!("Hours per period");
+                            print!("{}", chart::bars(&points, "h", style));
+                        }
+                        chart::Chart::Calendar => {
+                            println!("Hours per day");
+                            print!(
+                                "{}",
+                                chart::calendar(&hours_per_day, args.week_start(), style)
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        format => {
+            let report = Report::new(&sessions, &filter, &calendar, &args, &warnings);
+            match format {
+                Format::Html => print!("{}", report.to_html()),
+                Format::Markdown => print!("{}", report.to_markdown()),
+                _ => match serde_json::to_string_pretty(&report) {
+                    Ok(json) => println!("{}", json),
+                    Err(_) => return sysexits::ExitCode::Software,
+                },
             }
         }
     }
 
-    println!("Estimated time was {}h", duration.num_hours());
-    println!("Found {} commits overall", commit_count);
+    if let Some(path) = sessions_output {
+        let file = match std::fs::File::create(path) {
+            Ok(file) => file,
+            Err(_) => return sysexits::ExitCode::CantCreat,
+        };
+        let mut writer = csv::Writer::new(std::io::BufWriter::new(file), args.delimiter());
+        match csv::write_sessions(&mut writer, &sessions, &filter, timezone) {
+            Ok(something) => something,
+            Err(_) => return sysexits::ExitCode::IoErr,
+        };
+    }
+
+    if let Some(path) = ics_output {
+        match std::fs::write(path, ical::calendar(&sessions, &args.repository())) {
+            Ok(something) => something,
+            Err(_) => return sysexits::ExitCode::CantCreat,
+        };
+    }
+
+    if let (Some(tracker), Some(path)) = (args.tracker(), tracker_output) {
+        let entries = match tracker.export(
+            &sessions,
+            &args.tracker_mapping(),
+            &args.repository(),
+            timezone,
+            args.delimiter(),
+        ) {
+            Ok(entries) => entries,
+            Err(_) => return sysexits::ExitCode::Software,
+        };
+        match std::fs::write(path, entries) {
+            Ok(something) => something,
+            Err(_) => return sysexits::ExitCode::CantCreat,
+        };
+    }
+
+    if let Some(path) = coupling_dot {
+        let pairs = Coupling::analyse(
+            &sessions,
+            &filter,
+            args.coupling().unwrap_or(coupling::CouplingLevel::File),
+            args.coupling_depth(),
+            args.coupling_thresholds(),
+        );
+        match std::fs::write(path, coupling::dot(&pairs)) {
+            Ok(something) => something,
+            Err(_) => return sysexits::ExitCode::CantCreat,
+        };
+    }
 
-    if let Some(path) = args.take_output() {
-        let mut file = match std::fs::File::create(path) {
+    if let Some(path) = tickets_output {
+        let file = match std::fs::File::create(path) {
             Ok(file) => file,
             Err(_) => return sysexits::ExitCode::CantCreat,
         };
-        let mut sorted_per_day_data = vec![];
-        for key in commits_per_day.keys() {
-            let commit_count = commits_per_day[key];
-            let loc = loc_per_day[key];
-            sorted_per_day_data.push((*key, commit_count, loc));
-        }
-        sorted_per_day_data.sort_by_cached_key(|(k, _, _)| *k);
-        match writeln!(file, "Date, Commits, Loc") {
+        let mut writer = csv::Writer::new(std::io::BufWriter::new(file), args.delimiter());
+        let tickets = Ticket::collect(
+            &sessions,
+            &filter,
+            timezone,
+            args.time_attribution(),
+            &args.ticket_patterns(),
+        );
+        match csv::write_tickets(&mut writer, &tickets) {
+            Ok(something) => something,
+            Err(_) => return sysexits::ExitCode::IoErr,
+        };
+    }
+
+    if let Some(path) = hotspots_output {
+        let file = match std::fs::File::create(path) {
+            Ok(file) => file,
+            Err(_) => return sysexits::ExitCode::CantCreat,
+        };
+        let mut writer = csv::Writer::new(std::io::BufWriter::new(file), args.delimiter());
+        let hotspots = Hotspots::new(
+            &sessions,
+            &filter,
+            timezone,
+            args.hotspot_depth(),
+            args.hotspot_sort_by(),
+        );
+        match csv::write_hotspots(&mut writer, &hotspots) {
             Ok(something) => something,
             Err(_) => return sysexits::ExitCode::IoErr,
         };
-        for (date, commits, loc) in sorted_per_day_data {
-            match writeln!(file, "{}, {}, {}", date, commits, loc) {
+    }
+
+    if let Some(path) = sqlite_output {
+        let commits: Vec<_> = parsed_commits.iter().rev().collect();
+        match sqlite::export(&path, &commits, &sessions, &warnings) {
+            Ok(something) => something,
+            Err(err) => {
+                eprintln!(
+                    "The SQLite database '{}' could not be written, {}.",
+                    path.display(),
+                    err
+                );
+                return sysexits::ExitCode::CantCreat;
+            }
+        };
+    }
+
+    if let Some(path) = punch_card_output {
+        let file = match std::fs::File::create(path) {
+            Ok(file) => file,
+            Err(_) => return sysexits::ExitCode::CantCreat,
+        };
+        let mut writer = csv::Writer::new(std::io::BufWriter::new(file), args.delimiter());
+        let punch_card = PunchCard::new(&sessions, timezone, args.week_start());
+        match csv::write_punch_card(&mut writer, &punch_card) {
+            Ok(something) => something,
+            Err(_) => return sysexits::ExitCode::IoErr,
+        };
+    }
+
+    if let Some(directory) = svg_output {
+        if std::fs::create_dir_all(&directory).is_err() {
+            return sysexits::ExitCode::CantCreat;
+        }
+        let days = Activity::per_day(&sessions, &filter, timezone);
+        for chart in args.svg_charts() {
+            let svg = chart.render(&days, &calendar, args.week_start(), args.svg_size());
+            match std::fs::write(directory.join(chart.file_name()), svg) {
                 Ok(something) => something,
-                Err(_) => return sysexits::ExitCode::IoErr,
+                Err(_) => return sysexits::ExitCode::CantCreat,
             };
         }
     }
 
+    if let Some(path) = output {
+        let file = match std::fs::File::create(path) {
+            Ok(file) => file,
+            Err(_) => return sysexits::ExitCode::CantCreat,
+        };
+        let mut writer = csv::Writer::new(std::io::BufWriter::new(file), args.delimiter());
+        let result = if !args.group_by().is_empty() {
+            let grouping = Grouping::new(
+                args.group_by(),
+                &args.group_metrics(),
+                &sessions,
+                &filter,
+                timezone,
+                args.time_attribution(),
+                &args.repository(),
+            );
+
+            csv::write_groups(&mut writer, &grouping)
+        } else if args.per_commit() {
+            csv::write_commits(
+                &mut writer,
+                &sessions,
+                &filter,
+                &calendar,
+                timezone,
+                &columns,
+            )
+        } else {
+            let days = Activity::per_day(&sessions, &filter, timezone);
+            let periods = calendar.aggregate(&days);
+            let authors: BTreeSet<&str> = if args.author_columns() {
+                periods
+                    .values()
+                    .flat_map(|activity| activity.authors().keys())
+                    .map(String::as_str)
+                    .collect()
+            } else {
+                BTreeSet::new()
+            };
+
+            csv::write_periods(
+                &mut writer,
+                &periods,
+                &columns,
+                &authors.into_iter().collect::<Vec<_>>(),
+            )
+        };
+        match result {
+            Ok(something) => something,
+            Err(_) => return sysexits::ExitCode::IoErr,
+        };
+    }
+
     sysexits::ExitCode::Ok
 }
@@ -0,0 +1,430 @@
+//! The ownership of files and directories and the resulting bus factor.
+//!
+//! The change history of every path is attributed to its authors, weighted by
+//! the changed lines or the number of commits. The author with the largest
+//! share owns the path. The bus factor is the smallest number of authors who
+//! together hold the majority of the history, i.e. how many people may leave
+//! before most of the knowledge about a path is gone.
+
+use std::collections::HashMap;
+
+use crate::{
+    authors::CanonicalNames,
+    hotspots::Renames,
+    session::Session,
+    table::{Alignment, Table},
+    timezone::Timezone,
+    Commit,
+};
+
+/// What a change is weighted by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Weight {
+    /// The inserted and deleted lines, at least one per change.
+    Loc,
+
+    /// Every commit counts the same.
+    Commits,
+}
+
+/// The settings of the ownership analysis.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Options {
+    /// The date the staleness of the owners is measured from.
+    pub as_of: chrono::NaiveDate,
+
+    /// The number of path components to aggregate directories by.
+    pub depth: usize,
+
+    /// The number of days after which the weight of a change is halved, if
+    /// older changes should count less.
+    pub half_life: Option<f64>,
+
+    /// The number of days without a commit after which an owner is stale.
+    pub stale_after: i64,
+
+    /// What a change is weighted by.
+    pub weight: Weight,
+}
+
+/// The number of days after which the weight of a change is halved.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HalfLife(f64);
+
+impl From<HalfLife> for f64 {
+    fn from(half_life: HalfLife) -> Self {
+        half_life.0
+    }
+}
+
+impl std::fmt::Display for HalfLife {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::str::FromStr for HalfLife {
+    type Err = HalfLifeParseError;
+
+    fn from_str(half_life: &str) -> Result<Self, Self::Err> {
+        let days: f64 = half_life
+            .parse()
+            .map_err(|_| HalfLifeParseError::NotANumber)?;
+
+        if days.is_finite() && days > 0.0 {
+            Ok(Self(days))
+        } else {
+            Err(HalfLifeParseError::NotPositive)
+        }
+    }
+}
+
+/// The set of errors which may occur.
+#[derive(Debug)]
+pub enum HalfLifeParseError {
+    /// The half-life is not a number.
+    NotANumber,
+
+    /// The half-life is zero, negative or infinite.
+    NotPositive,
+}
+
+impl std::fmt::Display for HalfLifeParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotANumber => write!(f, "expected a number of days"),
+            Self::NotPositive => write!(f, "the half-life must be a positive number of days"),
+        }
+    }
+}
+
+impl std::error::Error for HalfLifeParseError {}
+
+/// The ownership of a single file or directory.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Area {
+    /// The path of the file or directory.
+    path: String,
+
+    /// The author with the largest share.
+    owner: String,
+
+    /// The owner's share of the weighted history, between 0 and 1.
+    share: f64,
+
+    /// The number of authors who changed the path.
+    authors: usize,
+
+    /// The smallest number of authors holding the majority of the history.
+    bus_factor: usize,
+
+    /// The date of the owner's last commit anywhere in the history.
+    owner_last_commit: chrono::DateTime<chrono::FixedOffset>,
+
+    /// Whether the owner has not committed for a while.
+    stale: bool,
+}
+
+impl Area {
+    /// The getter method for the field `authors` of the corresponding struct.
+    pub fn authors(&self) -> usize {
+        self.authors
+    }
+
+    /// The getter method for the field `bus_factor` of the corresponding
+    /// struct.
+    pub fn bus_factor(&self) -> usize {
+        self.bus_factor
+    }
+
+    /// The getter method for the field `owner` of the corresponding struct.
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+
+    /// The getter method for the field `owner_last_commit` of the
+    /// corresponding struct.
+    pub fn owner_last_commit(&self) -> &chrono::DateTime<chrono::FixedOffset> {
+        &self.owner_last_commit
+    }
+
+    /// The getter method for the field `path` of the corresponding struct.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The getter method for the field `share` of the corresponding struct.
+    pub fn share(&self) -> f64 {
+        self.share
+    }
+
+    /// The getter method for the field `stale` of the corresponding struct.
+    pub fn stale(&self) -> bool {
+        self.stale
+    }
+}
+
+/// The ownership of all changed files and their directories.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Ownership {
+    /// The changed files.
+    files: Vec<Area>,
+
+    /// The directories of the changed files, up to a certain depth.
+    directories: Vec<Area>,
+}
+
+impl Ownership {
+    /// Attributes the changes of the files matching the given filter to their
+    /// canonical authors, see `CanonicalNames`.
+    ///
+    /// Renamed files are tracked by their latest path. The age of a change
+    /// for the time decay is measured relative to the last commit of the
+    /// given sessions, the staleness of an owner relative to
+    /// `Options::as_of`. The dates are converted into the given time zone.
+    /// The areas are sorted by their bus factor and the owner's share, such
+    /// that the riskiest come first.
+    pub fn new(
+        sessions: &[Session],
+        filter: &crate::Filter,
+        timezone: Timezone,
+        options: Options,
+    ) -> Self {
+        let commits: Vec<&Commit> = sessions
+            .iter()
+            .flat_map(|session| session.commits().iter().copied())
+            .collect();
+        let latest = match commits.iter().map(|commit| *commit.date()).max() {
+            Some(latest) => latest,
+            None => {
+                return Self {
+                    files: vec![],
+                    directories: vec![],
+                }
+            }
+        };
+        let renames = Renames::new(commits.iter().rev().copied());
+        let names = CanonicalNames::new(sessions, filter);
+        let mut last_commits: HashMap<&str, chrono::DateTime<chrono::FixedOffset>> = HashMap::new();
+        let mut files: HashMap<String, HashMap<&str, f64>> = HashMap::new();
+        let mut directories: HashMap<String, HashMap<&str, f64>> = HashMap::new();
+
+        for commit in &commits {
+            let author = names.get(commit.author());
+            let date = timezone.convert(commit.date());
+            let last = last_commits.entry(author).or_insert(date);
+            *last = (*last).max(date);
+
+            let decay = options.half_life.map_or(1.0, |half_life| {
+                let age = (latest - *commit.date()).num_seconds() as f64 / 86_400.0;
+
+                0.5_f64.powf(age / half_life)
+            });
+
+            for loc in commit.locs().iter().filter(|loc| filter.check_loc(loc)) {
+                let path = renames.resolve(loc);
+                let directory = crate::hotspots::directory(&path, options.depth);
+                let weight = decay
+                    * match options.weight {
+                        Weight::Loc => loc.loc_stats().churn().max(1) as f64,
+                        Weight::Commits => 1.0,
+                    };
+
+                for (map, key) in [(&mut files, path), (&mut directories, directory)] {
+                    *map.entry(key).or_default().entry(author).or_default() += weight;
+                }
+            }
+        }
+
+        let build = |map: HashMap<String, HashMap<&str, f64>>| {
+            let mut areas: Vec<Area> = map
+                .into_iter()
+                .map(|(path, weights)| {
+                    let mut weights: Vec<(&str, f64)> = weights.into_iter().collect();
+                    weights.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+                    let total: f64 = weights.iter().map(|(_, weight)| weight).sum();
+                    let mut cumulated = 0.0;
+                    let bus_factor = weights
+                        .iter()
+                        .take_while(|(_, weight)| {
+                            let majority = cumulated > total / 2.0;
+                            cumulated += weight;
+                            !majority
+                        })
+                        .count();
+                    let (owner, weight) = weights[0];
+                    let owner_last_commit = last_commits[owner];
+
+                    Area {
+                        path,
+                        owner: owner.to_owned(),
+                        share: if total > 0.0 { weight / total } else { 0.0 },
+                        authors: weights.len(),
+                        bus_factor,
+                        owner_last_commit,
+                        stale: (options.as_of - owner_last_commit.naive_local().date()).num_days()
+                            > options.stale_after,
+                    }
+                })
+                .collect();
+
+            areas.sort_by(|a, b| {
+                a.bus_factor
+                    .cmp(&b.bus_factor)
+                    .then_with(|| b.share.total_cmp(&a.share))
+                    .then_with(|| a.path.cmp(&b.path))
+            });
+            areas
+        };
+
+        Self {
+            files: build(files),
+            directories: build(directories),
+        }
+    }
+
+    /// The getter method for the field `directories` of the corresponding
+    /// struct.
+    pub fn directories(&self) -> &[Area] {
+        &self.directories
+    }
+
+    /// The getter method for the field `files` of the corresponding struct.
+    pub fn files(&self) -> &[Area] {
+        &self.files
+    }
+
+    /// Converts the files and directories into a table each.
+    pub fn to_tables(&self) -> [Table; 2] {
+        [("File", &self.files), ("Directory", &self.directories)].map(|(path, areas)| {
+            let mut table = Table::new(&[
+                (path, Alignment::Left),
+                ("Owner", Alignment::Left),
+                ("Share", Alignment::Right),
+                ("Authors", Alignment::Right),
+                ("Bus Factor", Alignment::Right),
+                ("Owner's Last Commit", Alignment::Left),
+                ("Stale", Alignment::Left),
+            ]);
+
+            for area in areas {
+                table.push(vec![
+                    area.path().to_owned(),
+                    area.owner().to_owned(),
+                    format!("{:.1}%", area.share() * 100.0),
+                    area.authors().to_string(),
+                    area.bus_factor().to_string(),
+                    area.owner_last_commit().format("%Y-%m-%d").to_string(),
+                    if area.stale() { "yes" } else { "" }.to_owned(),
+                ]);
+            }
+
+            table
+        })
+    }
+
+    /// Keeps the given number of files and directories.
+    pub fn truncate(&mut self, count: usize) {
+        self.files.truncate(count);
+        self.directories.truncate(count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HalfLife, Options, Ownership, Weight};
+    use crate::session::Session;
+
+    const LOG: &str = "commit 1111111111111111111111111111111111111111
+Author: Alice <alice@example.com>
+Date:   Wed Jun 1 09:00:00 2022 +0000
+
+    First commit
+
+1\t0\tREADME.md
+";
+
+    #[test]
+    fn half_life_must_be_positive() {
+        assert_eq!(f64::from("7.5".parse::<HalfLife>().unwrap()), 7.5);
+        for input in ["0", "-1", "inf", "NaN", "week"] {
+            assert!(input.parse::<HalfLife>().is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn staleness_is_measured_from_the_given_date() {
+        let (commits, _) = crate::Commit::parse_log(LOG);
+        let sessions = Session::detect(commits.iter().rev(), 3);
+        let stale = |as_of: &str| {
+            let options = Options {
+                as_of: as_of.parse().unwrap(),
+                depth: 1,
+                half_life: None,
+                stale_after: 30,
+                weight: Weight::Loc,
+            };
+            let ownership = Ownership::new(
+                &sessions,
+                &crate::Filter::default(),
+                crate::timezone::Timezone::Utc,
+                options,
+            );
+
+            ownership.files()[0].stale()
+        };
+
+        assert!(!stale("2022-07-01"));
+        assert!(stale("2022-07-02"));
+    }
+
+    #[test]
+    fn owners_are_canonical_authors() {
+        let (commits, _) = crate::Commit::parse_log(
+            "commit 3333333333333333333333333333333333333333
+Author: Bob <bob@example.com>
+Date:   Wed Jun 1 11:00:00 2022 +0000
+
+    Third commit
+
+2\t0\tREADME.md
+
+commit 2222222222222222222222222222222222222222
+Author: alice <ALICE@example.com>
+Date:   Wed Jun 1 10:00:00 2022 +0000
+
+    Second commit
+
+2\t0\tREADME.md
+
+commit 1111111111111111111111111111111111111111
+Author: Alice <alice@example.com>
+Date:   Wed Jun 1 09:00:00 2022 +0000
+
+    First commit
+
+1\t0\tREADME.md
+",
+        );
+        let sessions = Session::detect(commits.iter().rev(), 3);
+        let ownership = Ownership::new(
+            &sessions,
+            &crate::Filter::default(),
+            crate::timezone::Timezone::Utc,
+            Options {
+                as_of: "2022-06-01".parse().unwrap(),
+                depth: 1,
+                half_life: None,
+                stale_after: 30,
+                weight: Weight::Loc,
+            },
+        );
+        let area = &ownership.files()[0];
+
+        assert_eq!(area.owner(), "Alice");
+        assert_eq!(area.authors(), 2);
+        assert!((area.share() - 0.6).abs() < 1e-9);
+        assert_eq!(area.bus_factor(), 1);
+    }
+}
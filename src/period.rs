@@ -0,0 +1,387 @@
+//! The grouping of calendar days into longer periods.
+//!
+//! Periods are either regular, such as ISO weeks or months, or defined by a
+//! sprint calendar file. A sprint calendar lists one named period per line,
+//! with the name, the first and the last day separated by commas:
+//!
+//! ```text
+//! # Name, first day, last day
+//! Sprint 1, 2022-06-01, 2022-06-14
+//! Sprint 2, 2022-06-15, 2022-06-28
+//! ```
+
+use std::collections::BTreeMap;
+
+use chrono::Datelike;
+
+/// The length of regular periods.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Granularity {
+    /// A single calendar day.
+    Day,
+
+    /// Seven days, beginning with the configured week start.
+    Week,
+
+    /// A calendar month.
+    Month,
+
+    /// Three calendar months, beginning in January, April, July or October.
+    Quarter,
+
+    /// A calendar year.
+    Year,
+}
+
+/// The first day of a week.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum WeekStart {
+    /// Weeks begin on Monday, as in ISO 8601.
+    Monday,
+
+    /// Weeks begin on Tuesday.
+    Tuesday,
+
+    /// Weeks begin on Wednesday.
+    Wednesday,
+
+    /// Weeks begin on Thursday.
+    Thursday,
+
+    /// Weeks begin on Friday.
+    Friday,
+
+    /// Weeks begin on Saturday, as in parts of the Middle East.
+    Saturday,
+
+    /// Weeks begin on Sunday, as in the United States.
+    Sunday,
+}
+
+impl From<WeekStart> for chrono::Weekday {
+    fn from(week_start: WeekStart) -> Self {
+        match week_start {
+            WeekStart::Monday => Self::Mon,
+            WeekStart::Tuesday => Self::Tue,
+            WeekStart::Wednesday => Self::Wed,
+            WeekStart::Thursday => Self::Thu,
+            WeekStart::Friday => Self::Fri,
+            WeekStart::Saturday => Self::Sat,
+            WeekStart::Sunday => Self::Sun,
+        }
+    }
+}
+
+/// The number of days the given weekday is after the given first day of a
+/// week, between 0 and 6.
+pub fn days_into_week(weekday: chrono::Weekday, week_start: chrono::Weekday) -> u32 {
+    (7 + weekday.num_days_from_monday() - week_start.num_days_from_monday()) % 7
+}
+
+/// The first day of the week the given day belongs to.
+pub fn first_day_of_week(
+    date: chrono::NaiveDate,
+    week_start: chrono::Weekday,
+) -> chrono::NaiveDate {
+    date - chrono::Duration::days(days_into_week(date.weekday(), week_start).into())
+}
+
+/// A named span of calendar days.
+///
+/// Periods are ordered by their first day.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize)]
+pub struct Period {
+    /// The first day.
+    start: chrono::NaiveDate,
+
+    /// The last day.
+    end: chrono::NaiveDate,
+
+    /// The display name, e.g. `2022-W22` or `Sprint 1`.
+    name: String,
+}
+
+impl Period {
+    /// The getter method for the field `end` of the corresponding struct.
+    pub fn end(&self) -> chrono::NaiveDate {
+        self.end
+    }
+
+    /// The getter method for the field `name` of the corresponding struct.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Extracts the named periods from the given sprint calendar.
+    ///
+    /// Empty lines and lines starting with `#` are ignored. The sprints must
+    /// not overlap.
+    pub fn parse_sprints(calendar: &str) -> Result<Vec<Self>, SprintParseError> {
+        let mut sprints = vec![];
+
+        for (index, line) in calendar.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut fields = line.rsplitn(3, ',').map(str::trim);
+            let (end, start, name) = match (fields.next(), fields.next(), fields.next()) {
+                (Some(end), Some(start), Some(name)) => (end, start, name),
+                _ => return Err(SprintParseError::FieldMissing(index + 1)),
+            };
+            let parse_date = |date| {
+                chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                    .map_err(|e| SprintParseError::DateFailed(index + 1, e))
+            };
+            let sprint = Self {
+                start: parse_date(start)?,
+                end: parse_date(end)?,
+                name: name.to_owned(),
+            };
+
+            if sprint.end < sprint.start {
+                return Err(SprintParseError::EndBeforeStart(index + 1));
+            }
+            sprints.push((sprint, index + 1));
+        }
+        sprints.sort();
+
+        if let Some(pair) = sprints
+            .windows(2)
+            .find(|pair| pair[1].0.start <= pair[0].0.end)
+        {
+            let (first, second) = (pair[0].1.min(pair[1].1), pair[0].1.max(pair[1].1));
+
+            return Err(SprintParseError::Overlap(first, second));
+        }
+
+        Ok(sprints.into_iter().map(|(sprint, _)| sprint).collect())
+    }
+
+    /// The getter method for the field `start` of the corresponding struct.
+    pub fn start(&self) -> chrono::NaiveDate {
+        self.start
+    }
+}
+
+/// The set of errors which may occur.
+#[derive(Debug)]
+pub enum SprintParseError {
+    /// A date in the given line could not be parsed.
+    DateFailed(usize, chrono::ParseError),
+
+    /// The sprint in the given line ends before it starts.
+    EndBeforeStart(usize),
+
+    /// The given line does not consist of a name and two dates.
+    FieldMissing(usize),
+
+    /// The sprints in the given lines share some days.
+    Overlap(usize, usize),
+}
+
+impl std::fmt::Display for SprintParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DateFailed(line, error) => write!(f, "line {}: {}", line, error),
+            Self::EndBeforeStart(line) => {
+                write!(f, "line {}: the sprint ends before it starts", line)
+            }
+            Self::FieldMissing(line) => {
+                write!(
+                    f,
+                    "line {}: expected a name, the first and the last day",
+                    line
+                )
+            }
+            Self::Overlap(first, second) => {
+                write!(f, "lines {} and {}: the sprints overlap", first, second)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SprintParseError {}
+
+/// The rules to group calendar days into periods.
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Calendar {
+    /// Periods of the same length.
+    Regular {
+        /// The length of the periods.
+        granularity: Granularity,
+
+        /// The first day of a week.
+        week_start: chrono::Weekday,
+    },
+
+    /// Named periods, such as sprints, ordered by their first day.
+    Sprints(Vec<Period>),
+}
+
+impl Calendar {
+    /// Sums up the daily activity per period.
+    ///
+    /// Days which do not belong to any period are left out.
+    pub fn aggregate(
+        &self,
+        days: &BTreeMap<chrono::NaiveDate, crate::activity::Activity>,
+    ) -> BTreeMap<Period, crate::activity::Activity> {
+        let mut periods: BTreeMap<Period, crate::activity::Activity> = BTreeMap::new();
+
+        for (date, activity) in days {
+            if let Some(period) = self.period(*date) {
+                periods.entry(period).or_default().merge(activity);
+            }
+        }
+
+        periods
+    }
+
+    /// Whether every period is a single calendar day.
+    pub fn is_daily(&self) -> bool {
+        matches!(
+            self,
+            Self::Regular {
+                granularity: Granularity::Day,
+                ..
+            }
+        )
+    }
+
+    /// Determines the period the given day belongs to.
+    pub fn period(&self, date: chrono::NaiveDate) -> Option<Period> {
+        let (granularity, week_start) = match self {
+            Self::Regular {
+                granularity,
+                week_start,
+            } => (granularity, week_start),
+            Self::Sprints(sprints) => {
+                return sprints
+                    .iter()
+                    .find(|s| s.start <= date && date <= s.end)
+                    .cloned()
+            }
+        };
+        let ymd = |year, month, day| chrono::NaiveDate::from_ymd(year, month, day);
+
+        Some(match granularity {
+            Granularity::Day => Period {
+                start: date,
+                end: date,
+                name: date.to_string(),
+            },
+            Granularity::Week => {
+                let start = first_day_of_week(date, *week_start);
+
+                Period {
+                    start,
+                    end: start + chrono::Duration::days(6),
+                    name: if *week_start == chrono::Weekday::Mon {
+                        start.format("%G-W%V").to_string()
+                    } else {
+                        format!("Week of {}", start)
+                    },
+                }
+            }
+            Granularity::Month => {
+                let start = ymd(date.year(), date.month(), 1);
+
+                Period {
+                    start,
+                    end: next_month(start, 1).pred(),
+                    name: start.format("%Y-%m").to_string(),
+                }
+            }
+            Granularity::Quarter => {
+                let quarter = date.month0() / 3;
+                let start = ymd(date.year(), quarter * 3 + 1, 1);
+
+                Period {
+                    start,
+                    end: next_month(start, 3).pred(),
+                    name: format!("{}-Q{}", date.year(), quarter + 1),
+                }
+            }
+            Granularity::Year => Period {
+                start: ymd(date.year(), 1, 1),
+                end: ymd(date.year(), 12, 31),
+                name: date.year().to_string(),
+            },
+        })
+    }
+}
+
+/// The first day of the month `months` months after the given one.
+fn next_month(date: chrono::NaiveDate, months: u32) -> chrono::NaiveDate {
+    let month0 = date.month0() + months;
+
+    chrono::NaiveDate::from_ymd(date.year() + (month0 / 12) as i32, month0 % 12 + 1, 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{days_into_week, first_day_of_week, Period, SprintParseError};
+    use chrono::Weekday;
+
+    fn date(text: &str) -> chrono::NaiveDate {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn week_offsets() {
+        assert_eq!(days_into_week(Weekday::Mon, Weekday::Mon), 0);
+        assert_eq!(days_into_week(Weekday::Sun, Weekday::Mon), 6);
+        assert_eq!(days_into_week(Weekday::Mon, Weekday::Sun), 1);
+        assert_eq!(
+            first_day_of_week(date("2022-06-01"), Weekday::Sun),
+            date("2022-05-29")
+        );
+    }
+
+    #[test]
+    fn parse_sprints() {
+        let sprints = Period::parse_sprints(
+            "# Name, first day, last day\n\
+             Sprint 2, 2022-06-15, 2022-06-28\n\
+             \n\
+             Sprint, with comma, 2022-06-01, 2022-06-14\n",
+        )
+        .unwrap();
+
+        assert_eq!(sprints.len(), 2);
+        assert_eq!(sprints[0].name(), "Sprint, with comma");
+        assert_eq!(sprints[0].start(), date("2022-06-01"));
+        assert_eq!(sprints[1].end(), date("2022-06-28"));
+    }
+
+    #[test]
+    fn parse_sprints_rejects_invalid_lines() {
+        assert!(matches!(
+            Period::parse_sprints("Sprint 1, 2022-06-01"),
+            Err(SprintParseError::FieldMissing(1))
+        ));
+        assert!(matches!(
+            Period::parse_sprints("\nSprint 1, 2022-06-01, 2022-13-01"),
+            Err(SprintParseError::DateFailed(2, _))
+        ));
+        assert!(matches!(
+            Period::parse_sprints("Sprint 1, 2022-06-14, 2022-06-01"),
+            Err(SprintParseError::EndBeforeStart(1))
+        ));
+    }
+
+    #[test]
+    fn parse_sprints_rejects_overlaps() {
+        assert!(matches!(
+            Period::parse_sprints(
+                "Sprint 2, 2022-06-14, 2022-06-28\nSprint 1, 2022-06-01, 2022-06-14"
+            ),
+            Err(SprintParseError::Overlap(1, 2))
+        ));
+    }
+}
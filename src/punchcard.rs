@@ -0,0 +1,107 @@
+//! The activity per weekday and hour of the day.
+//!
+//! A punch card bins the commits and the working time of the detected
+//! sessions into a matrix with a row per weekday and a column per hour, which
+//! shows when a team actually works.
+
+use chrono::{Datelike, Timelike};
+
+use crate::{session::Session, timezone::Timezone};
+
+/// The values to show in a punch card.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum PunchCardValue {
+    /// The number of commits.
+    Commits,
+
+    /// The working time in minutes.
+    Minutes,
+}
+
+/// The activity of a single weekday.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Weekday {
+    /// The day of the week.
+    weekday: chrono::Weekday,
+
+    /// The number of commits per hour of the day.
+    commits: [usize; 24],
+
+    /// The working time in minutes per hour of the day.
+    minutes: [f64; 24],
+}
+
+impl Weekday {
+    /// The getter method for the field `commits` of the corresponding struct.
+    pub fn commits(&self) -> &[usize; 24] {
+        &self.commits
+    }
+
+    /// The getter method for the field `minutes` of the corresponding struct.
+    pub fn minutes(&self) -> &[f64; 24] {
+        &self.minutes
+    }
+
+    /// The values of the given kind per hour of the day.
+    pub fn values(&self, value: PunchCardValue) -> [f64; 24] {
+        match value {
+            PunchCardValue::Commits => self.commits.map(|c| c as f64),
+            PunchCardValue::Minutes => self.minutes,
+        }
+    }
+
+    /// The getter method for the field `weekday` of the corresponding struct.
+    pub fn weekday(&self) -> chrono::Weekday {
+        self.weekday
+    }
+}
+
+/// The activity per weekday and hour of the day.
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(transparent)]
+pub struct PunchCard {
+    /// The rows, beginning with the first day of a week.
+    weekdays: Vec<Weekday>,
+}
+
+impl PunchCard {
+    /// Bins the commits and the working time of the given sessions.
+    ///
+    /// The weekdays and hours are determined in the given time zone. The
+    /// working time between two commits is split at the full hours.
+    pub fn new(sessions: &[Session], timezone: Timezone, week_start: chrono::Weekday) -> Self {
+        let mut weekdays: Vec<Weekday> =
+            std::iter::successors(Some(week_start), |d| Some(d.succ()))
+                .take(7)
+                .map(|weekday| Weekday {
+                    weekday,
+                    commits: [0; 24],
+                    minutes: [0.0; 24],
+                })
+                .collect();
+        let row = |date: &chrono::NaiveDateTime| {
+            crate::period::days_into_week(date.weekday(), week_start) as usize
+        };
+
+        for session in sessions {
+            for commit in session.commits() {
+                let date = timezone.convert(commit.date()).naive_local();
+
+                weekdays[row(&date)].commits[date.hour() as usize] += 1;
+            }
+            for interval in session.intervals() {
+                for (time, work) in timezone.split_hours(interval.start(), interval.end()) {
+                    weekdays[row(&time)].minutes[time.hour() as usize] +=
+                        work.num_seconds() as f64 / 60.0;
+                }
+            }
+        }
+
+        Self { weekdays }
+    }
+
+    /// The getter method for the field `weekdays` of the corresponding struct.
+    pub fn weekdays(&self) -> &[Weekday] {
+        &self.weekdays
+    }
+}
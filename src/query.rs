@@ -0,0 +1,1159 @@
+//! A small query language over the parsed commits and their file changes.
+//!
+//! A query consists of clauses, each introduced by a keyword and each allowed
+//! at most once:
+//!
+//! * `where <condition> [and <condition>]...` keeps the matching records,
+//! * `group by <dimension>, ...` combines the records with the same values,
+//! * `select <column>, ...` chooses the aggregates to show after the
+//!   dimensions, by default `count()` and `sum(hours)`,
+//! * `order by <column> [asc|desc], ...` sorts the rows and
+//! * `limit <number>` keeps the first rows.
+//!
+//! For example, `group by author, month select sum(hours), count(), sum(added)`
+//! shows the working time, the number of commits and the inserted lines of
+//! every author per month. A record is a commit, or a changed file as soon as
+//! the query refers to a file, extension, directory or language. The working
+//! time of commits without any changed files is then kept as `(no files)`.
+
+use std::collections::BTreeSet;
+
+use crate::{
+    attribution::Attribution,
+    group::{group, Dimension, Record},
+    session::Session,
+    table::{Alignment, Table},
+    timezone::Timezone,
+};
+
+/// A number of a record to aggregate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Field {
+    /// The working time in hours.
+    Hours,
+
+    /// The number of inserted lines.
+    Added,
+
+    /// The number of deleted lines.
+    Removed,
+
+    /// The inserted minus the deleted lines.
+    Net,
+
+    /// The inserted plus the deleted lines.
+    Churn,
+
+    /// The number of changed files.
+    Files,
+}
+
+impl Field {
+    /// The name of this field in queries.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Hours => "hours",
+            Self::Added => "added",
+            Self::Removed => "removed",
+            Self::Net => "net",
+            Self::Churn => "churn",
+            Self::Files => "files",
+        }
+    }
+
+    /// The field with the given name, ignoring the case.
+    fn parse(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+
+        [
+            Self::Hours,
+            Self::Added,
+            Self::Removed,
+            Self::Net,
+            Self::Churn,
+            Self::Files,
+        ]
+        .into_iter()
+        .find(|field| field.name() == name)
+    }
+
+    /// The value of the given record.
+    fn value(self, record: &Record) -> f64 {
+        match self {
+            Self::Hours => record.hours(),
+            Self::Added => record.loc().added() as f64,
+            Self::Removed => record.loc().removed() as f64,
+            Self::Net => record.loc().net() as f64,
+            Self::Churn => record.loc().churn() as f64,
+            Self::Files => record.files().len() as f64,
+        }
+    }
+}
+
+/// A dimension or a field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operand {
+    /// A dimension.
+    Dimension(Dimension),
+
+    /// A field.
+    Field(Field),
+}
+
+impl Operand {
+    /// The name of this operand in queries.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Dimension(dimension) => dimension.name(),
+            Self::Field(field) => field.name(),
+        }
+    }
+
+    /// The dimension or field with the given name.
+    fn parse(name: &str) -> Result<Self, QueryError> {
+        Dimension::parse(name)
+            .map(Self::Dimension)
+            .or_else(|| Field::parse(name).map(Self::Field))
+            .ok_or_else(|| QueryError::UnknownColumn(name.to_owned()))
+    }
+
+    /// The value of the given record.
+    fn value(self, record: &Record, repository: &str) -> Value {
+        match self {
+            Self::Dimension(dimension) => Value::Text(dimension.value(record, repository)),
+            Self::Field(field) => Value::Number(field.value(record)),
+        }
+    }
+}
+
+/// The comparison of a condition.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operator {
+    /// `=`
+    Equal,
+
+    /// `!=`
+    NotEqual,
+
+    /// `<`
+    Less,
+
+    /// `<=`
+    LessOrEqual,
+
+    /// `>`
+    Greater,
+
+    /// `>=`
+    GreaterOrEqual,
+
+    /// `~`, whether the value contains the text, ignoring the case.
+    Contains,
+}
+
+/// A condition of the `where` clause.
+#[derive(Clone, Debug, PartialEq)]
+struct Condition {
+    /// The value to compare.
+    operand: Operand,
+
+    /// The comparison.
+    operator: Operator,
+
+    /// The value to compare with.
+    value: Value,
+}
+
+impl Condition {
+    /// Whether the given record fulfills this condition.
+    fn matches(&self, record: &Record, repository: &str) -> bool {
+        let value = self.operand.value(record, repository);
+        let ordering = value.compare(&self.value);
+
+        match self.operator {
+            Operator::Equal => ordering.is_eq(),
+            Operator::NotEqual => ordering.is_ne(),
+            Operator::Less => ordering.is_lt(),
+            Operator::LessOrEqual => ordering.is_le(),
+            Operator::Greater => ordering.is_gt(),
+            Operator::GreaterOrEqual => ordering.is_ge(),
+            Operator::Contains => value
+                .to_string()
+                .to_lowercase()
+                .contains(&self.value.to_string().to_lowercase()),
+        }
+    }
+}
+
+/// An aggregate function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Function {
+    /// The number of records or, given an operand, of its distinct values.
+    Count,
+
+    /// The sum of a field.
+    Sum,
+
+    /// The mean of a field.
+    Avg,
+
+    /// The minimum of a field or a dimension.
+    Min,
+
+    /// The maximum of a field or a dimension.
+    Max,
+}
+
+impl Function {
+    /// Aggregates the given operand of the given records.
+    fn apply(self, operand: Option<Operand>, records: &[&Record], repository: &str) -> Value {
+        let values = || {
+            records
+                .iter()
+                .filter_map(move |record| operand.map(|o| o.value(record, repository)))
+        };
+        let sum = || values().map(|value| value.number()).sum::<f64>();
+
+        match self {
+            Self::Count if operand.is_none() => Value::Number(records.len() as f64),
+            Self::Count => Value::Number(
+                values()
+                    .map(|value| value.to_string())
+                    .collect::<BTreeSet<_>>()
+                    .len() as f64,
+            ),
+            Self::Sum => Value::Number(sum()),
+            Self::Avg if records.is_empty() => Value::Number(0.0),
+            Self::Avg => Value::Number(sum() / records.len() as f64),
+            Self::Min => values()
+                .min_by(Value::compare)
+                .unwrap_or_else(|| Value::empty(operand)),
+            Self::Max => values()
+                .max_by(Value::compare)
+                .unwrap_or_else(|| Value::empty(operand)),
+        }
+    }
+
+    /// The name of this function in queries.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Count => "count",
+            Self::Sum => "sum",
+            Self::Avg => "avg",
+            Self::Min => "min",
+            Self::Max => "max",
+        }
+    }
+
+    /// The function with the given name, ignoring the case.
+    fn parse(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+
+        [Self::Count, Self::Sum, Self::Avg, Self::Min, Self::Max]
+            .into_iter()
+            .find(|function| function.name() == name)
+    }
+}
+
+/// A column of the `select` clause.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Item {
+    /// A dimension of the `group by` clause.
+    Dimension(Dimension),
+
+    /// An aggregate of all records of a group.
+    Aggregate(Function, Option<Operand>),
+}
+
+impl Item {
+    /// Whether the values of this column are numbers.
+    fn is_numeric(&self) -> bool {
+        match self {
+            Self::Dimension(_) => false,
+            Self::Aggregate(Function::Min | Function::Max, Some(Operand::Dimension(_))) => false,
+            Self::Aggregate(..) => true,
+        }
+    }
+
+    /// The text of this column in queries, e.g. `sum(hours)`.
+    fn name(&self) -> String {
+        match self {
+            Self::Dimension(dimension) => dimension.name().to_owned(),
+            Self::Aggregate(function, operand) => format!(
+                "{}({})",
+                function.name(),
+                operand.map(Operand::name).unwrap_or_default()
+            ),
+        }
+    }
+}
+
+/// A column of the `select` clause with its title.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Selection {
+    /// The column.
+    item: Item,
+
+    /// The title given with `as`, defaults to the column's text.
+    label: String,
+}
+
+/// A parsed query.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Query {
+    /// The conditions each record needs to fulfill.
+    conditions: Vec<Condition>,
+
+    /// The dimensions to group by.
+    group_by: Vec<Dimension>,
+
+    /// The maximum number of rows.
+    limit: Option<usize>,
+
+    /// The indices of the columns to sort by and whether to sort descending.
+    order_by: Vec<(usize, bool)>,
+
+    /// The columns to show.
+    select: Vec<Selection>,
+}
+
+impl Query {
+    /// Whether this query requires a record per changed file.
+    fn is_per_file(&self) -> bool {
+        let operands = self
+            .conditions
+            .iter()
+            .map(|condition| Some(condition.operand))
+            .chain(self.select.iter().map(|selection| match selection.item {
+                Item::Dimension(dimension) => Some(Operand::Dimension(dimension)),
+                Item::Aggregate(_, operand) => operand,
+            }));
+
+        self.group_by
+            .iter()
+            .any(|dimension| dimension.is_per_file())
+            || operands
+                .flatten()
+                .any(|operand| matches!(operand, Operand::Dimension(d) if d.is_per_file()))
+    }
+
+    /// Runs this query over the commits of the given sessions.
+    ///
+    /// See `Record::collect` for how the working time is attributed and the
+    /// dates are converted.
+    pub fn run(
+        &self,
+        sessions: &[Session],
+        filter: &crate::Filter,
+        timezone: Timezone,
+        attribution: Attribution,
+        repository: &str,
+    ) -> QueryResult {
+        let records = Record::collect(sessions, filter, timezone, self.is_per_file(), attribution);
+        let matching = records.iter().filter(|record| {
+            self.conditions
+                .iter()
+                .all(|condition| condition.matches(record, repository))
+        });
+
+        // Every row is paired with the values to sort it by, which differ
+        // from the shown ones for dimensions like the weekday.
+        let mut rows: Vec<(Vec<Value>, Vec<Value>)> = group(matching, &self.group_by, repository)
+            .into_iter()
+            .map(|(key, records)| {
+                self.select
+                    .iter()
+                    .map(|selection| match selection.item {
+                        Item::Dimension(dimension) => {
+                            let index = self.group_by.iter().position(|d| *d == dimension);
+
+                            (
+                                Value::Text(key[index.unwrap()].clone()),
+                                Value::Text(dimension.sort_key(records[0], repository)),
+                            )
+                        }
+                        Item::Aggregate(function, operand) => {
+                            let value = function.apply(operand, &records, repository);
+
+                            (value.clone(), value)
+                        }
+                    })
+                    .unzip()
+            })
+            .collect();
+
+        rows.sort_by(|(_, a), (_, b)| {
+            self.order_by
+                .iter()
+                .map(|(index, descending)| {
+                    let ordering = a[*index].compare(&b[*index]);
+
+                    if *descending {
+                        ordering.reverse()
+                    } else {
+                        ordering
+                    }
+                })
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        if let Some(limit) = self.limit {
+            rows.truncate(limit);
+        }
+
+        QueryResult {
+            columns: self.select.iter().map(|s| s.label.clone()).collect(),
+            numeric: self.select.iter().map(|s| s.item.is_numeric()).collect(),
+            rows: rows.into_iter().map(|(row, _)| row).collect(),
+        }
+    }
+}
+
+impl std::str::FromStr for Query {
+    type Err = QueryError;
+
+    fn from_str(query: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            tokens: tokenize(query)?.into_iter().peekable(),
+        };
+        let mut result = Self::default();
+        let mut clauses = BTreeSet::new();
+        let mut order_by = vec![];
+
+        while parser.tokens.peek().is_some() {
+            let clause = parser.word()?.to_lowercase();
+
+            if !clauses.insert(clause.clone()) {
+                return Err(QueryError::DuplicateClause(clause));
+            }
+            match clause.as_str() {
+                "where" => {
+                    result.conditions.push(parser.condition()?);
+                    while parser.eat_keyword("and") {
+                        result.conditions.push(parser.condition()?);
+                    }
+                }
+                "group" => {
+                    parser.keyword("by")?;
+                    result.group_by = parser.list(|parser| {
+                        let name = parser.word()?;
+
+                        Dimension::parse(&name).ok_or(QueryError::UnknownColumn(name))
+                    })?;
+                }
+                "select" => result.select = parser.list(Parser::selection)?,
+                "order" => {
+                    parser.keyword("by")?;
+                    order_by = parser.list(|parser| {
+                        let column = parser.item_or_label()?;
+                        let descending = parser.eat_keyword("desc");
+
+                        if !descending {
+                            parser.eat_keyword("asc");
+                        }
+                        Ok((column, descending))
+                    })?;
+                }
+                "limit" => {
+                    let limit = parser.word()?;
+
+                    result.limit = Some(
+                        limit
+                            .parse()
+                            .map_err(|_| QueryError::InvalidNumber(limit))?,
+                    );
+                }
+                _ => return Err(QueryError::UnexpectedToken(clause)),
+            }
+        }
+
+        if result.select.is_empty() {
+            result.select = [
+                Item::Aggregate(Function::Count, None),
+                Item::Aggregate(Function::Sum, Some(Operand::Field(Field::Hours))),
+            ]
+            .into_iter()
+            .map(|item| Selection {
+                label: item.name(),
+                item,
+            })
+            .collect();
+        }
+        let missing: Vec<Selection> = result
+            .group_by
+            .iter()
+            .map(|dimension| Item::Dimension(*dimension))
+            .filter(|item| !result.select.iter().any(|s| s.item == *item))
+            .map(|item| Selection {
+                label: item.name(),
+                item,
+            })
+            .collect();
+        result.select.splice(0..0, missing);
+        for selection in &result.select {
+            if let Item::Dimension(dimension) = selection.item {
+                if !result.group_by.contains(&dimension) {
+                    return Err(QueryError::NotGrouped(dimension.name().to_owned()));
+                }
+            }
+        }
+        let mut labels = BTreeSet::new();
+        for selection in &result.select {
+            if !labels.insert(&selection.label) {
+                return Err(QueryError::DuplicateLabel(selection.label.clone()));
+            }
+        }
+        for (column, descending) in order_by {
+            let index = result
+                .select
+                .iter()
+                .position(|s| s.label == column || s.item.name() == column)
+                .ok_or(QueryError::UnknownColumn(column))?;
+
+            result.order_by.push((index, descending));
+        }
+
+        Ok(result)
+    }
+}
+
+/// The result of a query.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueryResult {
+    /// The titles of the columns.
+    columns: Vec<String>,
+
+    /// Whether the values of a column are numbers.
+    numeric: Vec<bool>,
+
+    /// The rows.
+    rows: Vec<Vec<Value>>,
+}
+
+impl QueryResult {
+    /// The getter method for the field `columns` of the corresponding struct.
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// The getter method for the field `rows` of the corresponding struct.
+    pub fn rows(&self) -> &[Vec<Value>] {
+        &self.rows
+    }
+
+    /// Converts the result into an array with an object per row.
+    pub fn to_json(&self) -> serde_json::Value {
+        self.rows
+            .iter()
+            .map(|row| {
+                self.columns
+                    .iter()
+                    .cloned()
+                    .zip(row.iter().map(|value| match value {
+                        Value::Number(number) if number.fract() == 0.0 => {
+                            serde_json::json!(*number as i64)
+                        }
+                        Value::Number(number) => serde_json::json!(number),
+                        Value::Text(text) => serde_json::json!(text),
+                    }))
+                    .collect::<serde_json::Map<_, _>>()
+            })
+            .collect()
+    }
+
+    /// Converts the result into a table, aligning numbers to the right.
+    pub fn to_table(&self) -> Table {
+        let header: Vec<(&str, Alignment)> = self
+            .columns
+            .iter()
+            .zip(&self.numeric)
+            .map(|(title, numeric)| {
+                let alignment = if *numeric {
+                    Alignment::Right
+                } else {
+                    Alignment::Left
+                };
+
+                (title.as_str(), alignment)
+            })
+            .collect();
+        let mut table = Table::new(&header);
+
+        for row in &self.rows {
+            table.push(row.iter().map(Value::to_string).collect());
+        }
+
+        table
+    }
+}
+
+/// A value of a record or a result.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    /// A number.
+    Number(f64),
+
+    /// A text.
+    Text(String),
+}
+
+impl Value {
+    /// Compares numbers numerically and everything else as text.
+    ///
+    /// Texts consisting of digits, such as the values of `hour` and `year`,
+    /// count as numbers.
+    fn compare(&self, other: &Self) -> std::cmp::Ordering {
+        match (self.as_number(), other.as_number()) {
+            (Some(a), Some(b)) => a.total_cmp(&b),
+            _ => self.to_string().cmp(&other.to_string()),
+        }
+    }
+
+    /// The number of this value or of its text, if it looks like a number.
+    fn as_number(&self) -> Option<f64> {
+        match self {
+            Self::Number(number) => Some(*number),
+            Self::Text(text) => {
+                let digits = text.strip_prefix('-').unwrap_or(text);
+
+                if digits.starts_with(|c: char| c.is_ascii_digit())
+                    && digits.chars().all(|c| c.is_ascii_digit() || c == '.')
+                {
+                    text.parse().ok()
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// The value of an aggregate over no records.
+    fn empty(operand: Option<Operand>) -> Self {
+        match operand {
+            Some(Operand::Dimension(_)) => Self::Text(String::new()),
+            _ => Self::Number(0.0),
+        }
+    }
+
+    /// The number of this value, zero for texts.
+    fn number(&self) -> f64 {
+        match self {
+            Self::Number(number) => *number,
+            Self::Text(_) => 0.0,
+        }
+    }
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Number(number) if number.fract() == 0.0 => write!(f, "{:.0}", number),
+            Self::Number(number) => write!(f, "{:.2}", number),
+            Self::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+/// The set of errors which may occur.
+#[derive(Debug)]
+pub enum QueryError {
+    /// A clause was given more than once.
+    DuplicateClause(String),
+
+    /// Several columns have the same title.
+    DuplicateLabel(String),
+
+    /// The function cannot be applied to the given column.
+    InvalidArgument(String),
+
+    /// The text is not a valid number.
+    InvalidNumber(String),
+
+    /// A dimension is selected without grouping by it.
+    NotGrouped(String),
+
+    /// The character is not part of the language.
+    UnexpectedCharacter(char),
+
+    /// The query ended prematurely.
+    UnexpectedEnd,
+
+    /// The word or symbol was not expected at this position.
+    UnexpectedToken(String),
+
+    /// There is no such dimension, field or column.
+    UnknownColumn(String),
+
+    /// There is no such aggregate function.
+    UnknownFunction(String),
+
+    /// A quoted text is not closed.
+    UnterminatedText,
+}
+
+impl std::fmt::Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DuplicateClause(clause) => write!(f, "the clause '{}' is repeated", clause),
+            Self::DuplicateLabel(label) => write!(f, "the column '{}' is repeated", label),
+            Self::InvalidArgument(item) => write!(f, "'{}' needs a number", item),
+            Self::InvalidNumber(number) => write!(f, "'{}' is not a number", number),
+            Self::NotGrouped(dimension) => {
+                write!(f, "'{}' is selected but not grouped by", dimension)
+            }
+            Self::UnexpectedCharacter(character) => {
+                write!(f, "unexpected character '{}'", character)
+            }
+            Self::UnexpectedEnd => write!(f, "unexpected end"),
+            Self::UnexpectedToken(token) => write!(f, "unexpected '{}'", token),
+            Self::UnknownColumn(column) => write!(f, "unknown column '{}'", column),
+            Self::UnknownFunction(function) => write!(f, "unknown function '{}'", function),
+            Self::UnterminatedText => write!(f, "a quoted text is not closed"),
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+/// A word, quoted text or symbol of a query.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    /// A symbol such as `(` or `>=`.
+    Symbol(&'static str),
+
+    /// A quoted text.
+    Text(String),
+
+    /// An unquoted word or number.
+    Word(String),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Symbol(symbol) => write!(f, "{}", symbol),
+            Self::Text(text) => write!(f, "\"{}\"", text),
+            Self::Word(word) => write!(f, "{}", word),
+        }
+    }
+}
+
+/// Splits the given query into tokens.
+fn tokenize(query: &str) -> Result<Vec<Token>, QueryError> {
+    let is_word = |c: char| c.is_alphanumeric() || "_-.:/@+".contains(c);
+    let mut tokens = vec![];
+    let mut chars = query.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        } else if c == '\'' || c == '"' {
+            let mut text = String::new();
+
+            loop {
+                match chars.next() {
+                    Some(next) if next == c => break,
+                    Some(next) => text.push(next),
+                    None => return Err(QueryError::UnterminatedText),
+                }
+            }
+            tokens.push(Token::Text(text));
+        } else if is_word(c) {
+            let mut word = c.to_string();
+
+            while let Some(next) = chars.next_if(|next| is_word(*next)) {
+                word.push(next);
+            }
+            tokens.push(Token::Word(word));
+        } else {
+            let symbol = match (c, chars.next_if_eq(&'=').is_some()) {
+                ('!', true) => "!=",
+                ('<', true) => "<=",
+                ('>', true) => ">=",
+                ('=', true) => "=",
+                ('<', false) => "<",
+                ('>', false) => ">",
+                ('=', false) => "=",
+                ('~', false) => "~",
+                ('(', false) => "(",
+                (')', false) => ")",
+                (',', false) => ",",
+                ('*', false) => "*",
+                _ => return Err(QueryError::UnexpectedCharacter(c)),
+            };
+            tokens.push(Token::Symbol(symbol));
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// A recursive descent parser over the tokens of a query.
+struct Parser {
+    /// The remaining tokens.
+    tokens: std::iter::Peekable<std::vec::IntoIter<Token>>,
+}
+
+impl Parser {
+    /// Parses a condition such as `author = "Jane Doe"`.
+    fn condition(&mut self) -> Result<Condition, QueryError> {
+        let operand = Operand::parse(&self.word()?)?;
+        let operator = match self.next()? {
+            Token::Symbol("=") => Operator::Equal,
+            Token::Symbol("!=") => Operator::NotEqual,
+            Token::Symbol("<") => Operator::Less,
+            Token::Symbol("<=") => Operator::LessOrEqual,
+            Token::Symbol(">") => Operator::Greater,
+            Token::Symbol(">=") => Operator::GreaterOrEqual,
+            Token::Symbol("~") => Operator::Contains,
+            token => return Err(QueryError::UnexpectedToken(token.to_string())),
+        };
+        let value = match (self.next()?, operand) {
+            (Token::Symbol(symbol), _) => {
+                return Err(QueryError::UnexpectedToken(symbol.to_owned()))
+            }
+            (Token::Text(text) | Token::Word(text), Operand::Field(_))
+                if operator != Operator::Contains =>
+            {
+                Value::Number(text.parse().map_err(|_| QueryError::InvalidNumber(text))?)
+            }
+            (Token::Text(text) | Token::Word(text), _) => Value::Text(text),
+        };
+
+        Ok(Condition {
+            operand,
+            operator,
+            value,
+        })
+    }
+
+    /// Consumes the given keyword if it is next.
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        self.tokens
+            .next_if(
+                |token| matches!(token, Token::Word(word) if word.eq_ignore_ascii_case(keyword)),
+            )
+            .is_some()
+    }
+
+    /// Consumes the given symbol if it is next.
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        self.tokens
+            .next_if(|token| matches!(token, Token::Symbol(s) if *s == symbol))
+            .is_some()
+    }
+
+    /// Parses a dimension or an aggregate such as `sum(hours)`.
+    fn item(&mut self) -> Result<Item, QueryError> {
+        let name = self.word()?;
+
+        if !self.eat_symbol("(") {
+            return Dimension::parse(&name)
+                .map(Item::Dimension)
+                .ok_or(QueryError::UnknownColumn(name));
+        }
+
+        let function = Function::parse(&name).ok_or(QueryError::UnknownFunction(name))?;
+        let operand = if self.eat_symbol(")") {
+            None
+        } else if self.eat_symbol("*") {
+            self.symbol(")")?;
+            None
+        } else {
+            let operand = Operand::parse(&self.word()?)?;
+            self.symbol(")")?;
+            Some(operand)
+        };
+        let item = Item::Aggregate(function, operand);
+
+        match (function, operand) {
+            (Function::Count, _)
+            | (Function::Sum | Function::Avg, Some(Operand::Field(_)))
+            | (Function::Min | Function::Max, Some(_)) => Ok(item),
+            _ => Err(QueryError::InvalidArgument(item.name())),
+        }
+    }
+
+    /// Parses a column of the `order by` clause, either a column of the
+    /// `select` clause or its title.
+    fn item_or_label(&mut self) -> Result<String, QueryError> {
+        match self.tokens.peek() {
+            Some(Token::Text(_)) => self.next().map(|token| match token {
+                Token::Text(text) => text,
+                _ => unreachable!("the next token is a text"),
+            }),
+            Some(Token::Word(word))
+                if Dimension::parse(word).is_none() && Function::parse(word).is_none() =>
+            {
+                self.word()
+            }
+            _ => self.item().map(|item| item.name()),
+        }
+    }
+
+    /// Consumes the given keyword, ignoring the case.
+    fn keyword(&mut self, keyword: &str) -> Result<(), QueryError> {
+        let word = self.word()?;
+
+        if word.eq_ignore_ascii_case(keyword) {
+            Ok(())
+        } else {
+            Err(QueryError::UnexpectedToken(word))
+        }
+    }
+
+    /// Parses a list of items separated by commas.
+    fn list<T, F>(&mut self, mut item: F) -> Result<Vec<T>, QueryError>
+    where
+        F: FnMut(&mut Self) -> Result<T, QueryError>,
+    {
+        let mut items = vec![item(self)?];
+
+        while self.eat_symbol(",") {
+            items.push(item(self)?);
+        }
+
+        Ok(items)
+    }
+
+    /// Consumes the next token.
+    fn next(&mut self) -> Result<Token, QueryError> {
+        self.tokens.next().ok_or(QueryError::UnexpectedEnd)
+    }
+
+    /// Parses a column of the `select` clause with an optional title.
+    fn selection(&mut self) -> Result<Selection, QueryError> {
+        let item = self.item()?;
+        let label = if self.eat_keyword("as") {
+            match self.next()? {
+                Token::Text(label) | Token::Word(label) => label,
+                token => return Err(QueryError::UnexpectedToken(token.to_string())),
+            }
+        } else {
+            item.name()
+        };
+
+        Ok(Selection { item, label })
+    }
+
+    /// Consumes the given symbol.
+    fn symbol(&mut self, symbol: &str) -> Result<(), QueryError> {
+        match self.next()? {
+            Token::Symbol(s) if s == symbol => Ok(()),
+            token => Err(QueryError::UnexpectedToken(token.to_string())),
+        }
+    }
+
+    /// Consumes the next token, which needs to be a word.
+    fn word(&mut self) -> Result<String, QueryError> {
+        match self.next()? {
+            Token::Word(word) => Ok(word),
+            token => Err(QueryError::UnexpectedToken(token.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Query, QueryError, Value};
+    use crate::{attribution::Attribution, session::Session, timezone::Timezone};
+
+    /// Three commits of a single session at 22:00, 23:00 and 01:00 UTC.
+    const LOG: &str = "commit 3333333333333333333333333333333333333333
+Author: Bob <bob@example.com>
+Date:   Thu Jun 2 01:00:00 2022 +0000
+
+    fix: late
+
+2\t1\tsrc/main.rs
+
+commit 2222222222222222222222222222222222222222
+Author: Alice <alice@example.com>
+Date:   Wed Jun 1 23:00:00 2022 +0000
+
+    feat: more
+
+3\t0\tsrc/lib.rs
+1\t0\tREADME.md
+
+commit 1111111111111111111111111111111111111111
+Author: Alice <alice@example.com>
+Date:   Wed Jun 1 22:00:00 2022 +0000
+
+    feat: start
+
+5\t0\tsrc/lib.rs
+";
+
+    fn run(query: &str) -> Vec<Vec<String>> {
+        let (commits, _) = crate::Commit::parse_log(LOG);
+        let sessions = Session::detect(commits.iter().rev(), 3);
+        let filter = crate::Filter::default();
+        let result = query.parse::<Query>().unwrap().run(
+            &sessions,
+            &filter,
+            Timezone::Utc,
+            Attribution::Interval,
+            "repo",
+        );
+
+        result
+            .rows()
+            .iter()
+            .map(|row| row.iter().map(Value::to_string).collect())
+            .collect()
+    }
+
+    #[test]
+    fn parse_rejects_invalid_queries() {
+        let error = |query: &str| query.parse::<Query>().unwrap_err();
+
+        assert!(matches!(
+            error("group by author group by day"),
+            QueryError::DuplicateClause(_)
+        ));
+        assert!(matches!(
+            error("select count() as x, sum(hours) as x"),
+            QueryError::DuplicateLabel(label) if label == "x"
+        ));
+        assert!(matches!(
+            error("group by author select count() as author"),
+            QueryError::DuplicateLabel(_)
+        ));
+        assert!(matches!(
+            error("select sum(author)"),
+            QueryError::InvalidArgument(_)
+        ));
+        assert!(matches!(
+            error("where hours > many"),
+            QueryError::InvalidNumber(_)
+        ));
+        assert!(matches!(error("select author"), QueryError::NotGrouped(_)));
+        assert!(matches!(
+            error("where author = 'x"),
+            QueryError::UnterminatedText
+        ));
+        assert!(matches!(
+            error("select median(hours)"),
+            QueryError::UnknownFunction(_)
+        ));
+        assert!(matches!(
+            error("group by colour"),
+            QueryError::UnknownColumn(_)
+        ));
+        assert!(matches!(error("where"), QueryError::UnexpectedEnd));
+    }
+
+    #[test]
+    fn run_groups_and_sums() {
+        assert_eq!(
+            run("group by author select count(), sum(added) as added order by added desc"),
+            [["Alice", "2", "9"], ["Bob", "1", "2"]]
+        );
+        assert_eq!(
+            run("select count(author), sum(hours), max(day)"),
+            [["2", "3", "2022-06-02"]]
+        );
+    }
+
+    #[test]
+    fn run_compares_hours_as_numbers() {
+        assert_eq!(run("where hour >= 9 select count()"), [["2"]]);
+        assert_eq!(run("where hour < 3 select count()"), [["1"]]);
+        assert_eq!(
+            run("group by hour select count() order by hour desc"),
+            [["23", "1"], ["22", "1"], ["01", "1"]]
+        );
+    }
+
+    #[test]
+    fn run_attributes_files() {
+        assert_eq!(
+            run("where file ~ lib group by file select count(), sum(added)"),
+            [["src/lib.rs", "2", "8"]]
+        );
+    }
+
+    #[test]
+    fn run_matches_the_author_summaries() {
+        let (commits, _) = crate::Commit::parse_log(LOG);
+        let sessions = Session::detect(commits.iter().rev(), 3);
+        let filter = crate::Filter::default();
+        let authors = crate::authors::AuthorSummary::summarise(
+            &sessions,
+            &filter,
+            Timezone::Utc,
+            crate::authors::SortKey::Name,
+            crate::loc::LocMetric::Net,
+        );
+
+        for attribution in [Attribution::Interval, Attribution::Even, Attribution::Churn] {
+            let result = "group by author select author, sum(hours) order by author"
+                .parse::<Query>()
+                .unwrap()
+                .run(&sessions, &filter, Timezone::Utc, attribution, "repo");
+            let hours: Vec<String> = result.rows().iter().map(|row| row[1].to_string()).collect();
+
+            assert_eq!(
+                hours,
+                authors
+                    .iter()
+                    .map(|author| author.hours().to_string())
+                    .collect::<Vec<_>>(),
+                "{}",
+                attribution
+            );
+        }
+    }
+
+    #[test]
+    fn run_sorts_weekdays_and_merges_authors() {
+        let (commits, _) = crate::Commit::parse_log(
+            "commit 3333333333333333333333333333333333333333
+Author: alice <ALICE@example.com>
+Date:   Mon Jun 6 09:00:00 2022 +0000
+
+    Monday
+
+commit 2222222222222222222222222222222222222222
+Author: Alice <alice@example.com>
+Date:   Sun Jun 5 09:00:00 2022 +0000
+
+    Sunday
+
+commit 1111111111111111111111111111111111111111
+Author: Alice <alice@example.com>
+Date:   Fri Jun 3 09:00:00 2022 +0000
+
+    Friday
+",
+        );
+        let sessions = Session::detect(commits.iter().rev(), 3);
+        let run = |query: &str| {
+            query
+                .parse::<Query>()
+                .unwrap()
+                .run(
+                    &sessions,
+                    &crate::Filter::default(),
+                    Timezone::Utc,
+                    Attribution::Interval,
+                    "repo",
+                )
+                .rows()
+                .iter()
+                .map(|row| row.iter().map(Value::to_string).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            run("group by weekday select count() order by weekday"),
+            [["Mon", "1"], ["Fri", "1"], ["Sun", "1"]]
+        );
+        assert_eq!(
+            run("group by weekday select count() order by weekday desc"),
+            [["Sun", "1"], ["Fri", "1"], ["Mon", "1"]]
+        );
+        assert_eq!(run("group by author select count()"), [["Alice", "3"]]);
+    }
+}
@@ -0,0 +1,473 @@
+//! The machine-readable summary of an analysis.
+//!
+//! A report holds the headline numbers, the aggregates per author, period and
+//! group, the time shares, the tickets, the types of change, the languages,
+//! the hotspots, the coupled files, the ownership, the detected sessions, a
+//! punch card, the configuration which led to them and any parse warnings. It
+//! is serialised as a versioned JSON document such that scripts and
+//! dashboards do not need to scrape the text output.
+
+mod html;
+mod markdown;
+
+use std::collections::BTreeMap;
+
+use crate::{
+    authors::AuthorSummary,
+    session::{hours, Session},
+};
+
+/// The version of the report's JSON schema.
+///
+/// This number is increased whenever a field is renamed or removed.
+pub const VERSION: u32 = 2;
+
+/// The possible output formats.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// Human-readable text.
+    Text,
+
+    /// A versioned JSON document.
+    Json,
+
+    /// A Markdown document with a table per author, period and session.
+    Markdown,
+
+    /// A single HTML page with embedded charts.
+    Html,
+}
+
+/// The summary of an analysis.
+#[derive(Debug, serde::Serialize)]
+pub struct Report<'a> {
+    /// The version of the JSON schema.
+    version: u32,
+
+    /// The options which led to this report.
+    configuration: Configuration<'a>,
+
+    /// The headline numbers.
+    totals: Totals,
+
+    /// The aggregates per canonical author, ordered as requested.
+    authors: Vec<AuthorSummary>,
+
+    /// The aggregates per period, ordered by date.
+    periods: Vec<PeriodSummary>,
+
+    /// The aggregates per calendar day, ordered by date.
+    ///
+    /// These are used to draw charts, the periods hold the same information
+    /// when aggregating by day.
+    #[serde(skip)]
+    days: Vec<PeriodSummary>,
+
+    /// The working time per file, directory or language if requested by
+    /// `--time-by`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time_shares: Option<Vec<crate::attribution::TimeShare>>,
+
+    /// The working time per referenced ticket if requested by `--tickets`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tickets: Option<Vec<crate::tickets::Ticket>>,
+
+    /// The aggregates per Conventional Commits type if requested by
+    /// `--types`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    types: Option<crate::group::Grouping>,
+
+    /// The aggregates per language if requested by `--languages`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    languages: Option<crate::group::Grouping>,
+
+    /// The hotspots among the files and directories if requested by
+    /// `--hotspots`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hotspots: Option<crate::hotspots::Hotspots>,
+
+    /// The owners and bus factors of the files and directories if requested
+    /// by `--ownership`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ownership: Option<crate::ownership::Ownership>,
+
+    /// The pairs of paths which change together if requested by
+    /// `--coupling`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    coupling: Option<Vec<crate::coupling::Coupling>>,
+
+    /// The metrics per group if grouping by `--group-by`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    groups: Option<crate::group::Grouping>,
+
+    /// The detected work sessions, ordered by date.
+    sessions: Vec<SessionSummary>,
+
+    /// The commits and the working time per weekday and hour of the day.
+    punch_card: crate::punchcard::PunchCard,
+
+    /// The reasons why some commits were skipped.
+    warnings: Vec<String>,
+}
+
+impl<'a> Report<'a> {
+    /// Summarises the given sessions.
+    ///
+    /// The sessions are expected to be detected from the commits matching the
+    /// given filter.
+    pub fn new(
+        sessions: &[Session],
+        filter: &'a crate::Filter<'a>,
+        calendar: &'a crate::period::Calendar,
+        args: &crate::Args,
+        warnings: &[crate::ParseWarning],
+    ) -> Self {
+        let timezone = args.timezone();
+        let commits = || sessions.iter().flat_map(|s| s.commits().iter());
+        let mut authors = AuthorSummary::summarise(
+            sessions,
+            filter,
+            timezone,
+            args.sort_by(),
+            args.loc_metric(),
+        );
+        if let Some(top) = args.top() {
+            authors.truncate(top);
+        }
+
+        let days = crate::activity::Activity::per_day(sessions, filter, timezone);
+        let summarise = |(period, activity): (crate::period::Period, crate::activity::Activity)| {
+            PeriodSummary {
+                authors: activity
+                    .authors()
+                    .iter()
+                    .map(|(author, work)| (author.clone(), hours(*work)))
+                    .collect(),
+                commits: activity.commits(),
+                hours: hours(activity.work()),
+                loc: activity.loc(),
+                period,
+            }
+        };
+        let daily = crate::period::Calendar::Regular {
+            granularity: crate::period::Granularity::Day,
+            week_start: chrono::Weekday::Mon,
+        };
+
+        Self {
+            version: VERSION,
+            configuration: Configuration {
+                calendar,
+                duration: args.duration(),
+                filter,
+                loc_metric: args.loc_metric(),
+                time_attribution: args.time_attribution(),
+                time_by: args.time_by(),
+                timezone: timezone.to_string(),
+            },
+            totals: Totals {
+                commits: commits().count(),
+                first_commit: commits().map(|c| timezone.convert(c.date())).min(),
+                hours: sessions.iter().map(|s| hours(s.duration())).sum(),
+                last_commit: commits().map(|c| timezone.convert(c.date())).max(),
+                loc: commits().map(|c| c.loc_stats(filter)).sum(),
+                sessions: sessions.len(),
+            },
+            authors,
+            periods: calendar
+                .aggregate(&days)
+                .into_iter()
+                .map(summarise)
+                .collect(),
+            days: daily.aggregate(&days).into_iter().map(summarise).collect(),
+            time_shares: args.time_by().map(|level| {
+                let mut shares = crate::attribution::TimeShare::attribute(
+                    sessions,
+                    filter,
+                    timezone,
+                    args.time_attribution(),
+                    level,
+                    args.time_depth(),
+                );
+                if let Some(top) = args.top() {
+                    shares.truncate(top);
+                }
+                shares
+            }),
+            tickets: if args.tickets() {
+                let mut tickets = crate::tickets::Ticket::collect(
+                    sessions,
+                    filter,
+                    timezone,
+                    args.time_attribution(),
+                    &args.ticket_patterns(),
+                );
+                if let Some(top) = args.top() {
+                    tickets.truncate(top);
+                }
+                Some(tickets)
+            } else {
+                None
+            },
+            types: if args.types() {
+                Some(crate::conventional::breakdown(
+                    sessions,
+                    filter,
+                    timezone,
+                    args.time_attribution(),
+                    &args.repository(),
+                ))
+            } else {
+                None
+            },
+            languages: if args.languages() {
+                Some(crate::language::breakdown(
+                    sessions,
+                    filter,
+                    timezone,
+                    args.time_attribution(),
+                    &args.repository(),
+                ))
+            } else {
+                None
+            },
+            hotspots: if args.hotspots() {
+                let mut hotspots = crate::hotspots::Hotspots::new(
+                    sessions,
+                    filter,
+                    timezone,
+                    args.hotspot_depth(),
+                    args.hotspot_sort_by(),
+                );
+                if let Some(top) = args.top() {
+                    hotspots.truncate(top);
+                }
+                Some(hotspots)
+            } else {
+                None
+            },
+            ownership: if args.ownership() {
+                let mut ownership = crate::ownership::Ownership::new(
+                    sessions,
+                    filter,
+                    timezone,
+                    args.ownership_options(),
+                );
+                if let Some(top) = args.top() {
+                    ownership.truncate(top);
+                }
+                Some(ownership)
+            } else {
+                None
+            },
+            coupling: args.coupling().map(|level| {
+                let mut coupling = crate::coupling::Coupling::analyse(
+                    sessions,
+                    filter,
+                    level,
+                    args.coupling_depth(),
+                    args.coupling_thresholds(),
+                );
+                if let Some(top) = args.top() {
+                    coupling.truncate(top);
+                }
+                coupling
+            }),
+            groups: if args.group_by().is_empty() {
+                None
+            } else {
+                Some(crate::group::Grouping::new(
+                    args.group_by(),
+                    &args.group_metrics(),
+                    sessions,
+                    filter,
+                    timezone,
+                    args.time_attribution(),
+                    &args.repository(),
+                ))
+            },
+            sessions: sessions
+                .iter()
+                .map(|session| SessionSummary {
+                    authors: session.authors().into_iter().map(str::to_owned).collect(),
+                    commits: session.commits().len(),
+                    end: timezone.convert(session.end()),
+                    hours: hours(session.duration()),
+                    loc: session.loc(filter),
+                    original_end: *session.end(),
+                    original_start: *session.start(),
+                    start: timezone.convert(session.start()),
+                    subjects: session.subjects().into_iter().map(str::to_owned).collect(),
+                })
+                .collect(),
+            punch_card: crate::punchcard::PunchCard::new(sessions, timezone, args.week_start()),
+            warnings: warnings.iter().map(ToString::to_string).collect(),
+        }
+    }
+}
+
+impl Report<'_> {
+    /// The column titles of the author table, given the title of the LOC
+    /// column.
+    fn author_header<'a>(&self, loc: &'a str) -> [&'a str; 9] {
+        [
+            "Author",
+            "Hours",
+            "Share",
+            "Sessions",
+            "Commits",
+            "Active Days",
+            loc,
+            "First Commit",
+            "Last Commit",
+        ]
+    }
+
+    /// The rows of the author table, with the LOC summed up to the given
+    /// metric.
+    fn author_rows(&self, metric: crate::loc::LocMetric) -> Vec<Vec<String>> {
+        self.authors
+            .iter()
+            .map(|a| {
+                vec![
+                    a.name().to_owned(),
+                    format!("{:.2}", a.hours()),
+                    format!("{:.1}%", a.share() * 100.0),
+                    a.sessions().to_string(),
+                    a.commits().to_string(),
+                    a.active_days().to_string(),
+                    a.loc().get(metric).to_string(),
+                    a.first_commit().format("%Y-%m-%d %H:%M").to_string(),
+                    a.last_commit().format("%Y-%m-%d %H:%M").to_string(),
+                ]
+            })
+            .collect()
+    }
+}
+
+/// The options which led to a report.
+#[derive(Debug, serde::Serialize)]
+struct Configuration<'a> {
+    /// The periods to aggregate by.
+    calendar: &'a crate::period::Calendar,
+
+    /// The maximum number of hours between two commits of a session.
+    duration: u32,
+
+    /// The filter criteria.
+    filter: &'a crate::Filter<'a>,
+
+    /// The number the inserted and deleted lines are summed up to in the
+    /// headline numbers and tables.
+    loc_metric: crate::loc::LocMetric,
+
+    /// How the working time is distributed over the commits and files.
+    time_attribution: crate::attribution::Attribution,
+
+    /// What the working time is attributed to, if requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time_by: Option<crate::attribution::Level>,
+
+    /// The time zone all dates were converted into.
+    timezone: String,
+}
+
+/// The headline numbers of a report.
+#[derive(Debug, serde::Serialize)]
+struct Totals {
+    /// The number of matching commits.
+    commits: usize,
+
+    /// The date of the first matching commit.
+    first_commit: Option<chrono::DateTime<chrono::FixedOffset>>,
+
+    /// The estimated working time in hours.
+    hours: f64,
+
+    /// The date of the last matching commit.
+    last_commit: Option<chrono::DateTime<chrono::FixedOffset>>,
+
+    /// The inserted and deleted lines.
+    loc: crate::loc::LocStats,
+
+    /// The number of work sessions.
+    sessions: usize,
+}
+
+impl Totals {
+    /// The headline numbers as pairs of a name and a value.
+    ///
+    /// The LOC are summed up to the given metric, the inserted and deleted
+    /// lines are listed separately.
+    fn rows(&self, metric: crate::loc::LocMetric) -> Vec<Vec<String>> {
+        let date = |date: Option<chrono::DateTime<chrono::FixedOffset>>| {
+            date.map(|d| d.format("%Y-%m-%d %H:%M %:z").to_string())
+                .unwrap_or_default()
+        };
+
+        vec![
+            vec!["Estimated time".into(), format!("{:.2}h", self.hours)],
+            vec!["Commits".into(), self.commits.to_string()],
+            vec!["Sessions".into(), self.sessions.to_string()],
+            vec![
+                format!("LOC ({})", metric),
+                self.loc.get(metric).to_string(),
+            ],
+            vec!["Inserted lines".into(), self.loc.added().to_string()],
+            vec!["Deleted lines".into(), self.loc.removed().to_string()],
+            vec!["First commit".into(), date(self.first_commit)],
+            vec!["Last commit".into(), date(self.last_commit)],
+        ]
+    }
+}
+
+/// The aggregates of a single period.
+#[derive(Debug, serde::Serialize)]
+struct PeriodSummary {
+    /// The period.
+    #[serde(flatten)]
+    period: crate::period::Period,
+
+    /// The working time per author in hours.
+    authors: BTreeMap<String, f64>,
+
+    /// The number of commits.
+    commits: usize,
+
+    /// The working time in hours.
+    hours: f64,
+
+    /// The inserted and deleted lines.
+    loc: crate::loc::LocStats,
+}
+
+/// The summary of a single work session.
+#[derive(Debug, serde::Serialize)]
+struct SessionSummary {
+    /// The date of the first commit.
+    start: chrono::DateTime<chrono::FixedOffset>,
+
+    /// The date of the last commit.
+    end: chrono::DateTime<chrono::FixedOffset>,
+
+    /// The date of the first commit with the offset it was recorded with.
+    original_start: chrono::DateTime<chrono::FixedOffset>,
+
+    /// The date of the last commit with the offset it was recorded with.
+    original_end: chrono::DateTime<chrono::FixedOffset>,
+
+    /// The names of the contributing authors.
+    authors: Vec<String>,
+
+    /// The number of commits.
+    commits: usize,
+
+    /// The working time in hours.
+    hours: f64,
+
+    /// The inserted and deleted lines.
+    loc: crate::loc::LocStats,
+
+    /// The subjects of the first commits.
+    subjects: Vec<String>,
+}
@@ -0,0 +1,252 @@
+//! The rendering of reports as a self-contained HTML page.
+
+use std::fmt::Write;
+
+use crate::{
+    attribution::TimeShare,
+    coupling::Coupling,
+    punchcard::PunchCardValue,
+    svg::{self, escape, Size},
+    table::{Alignment, Table},
+    tickets::Ticket,
+};
+
+/// The style sheet embedded into every page.
+const STYLE: &str = "body{font-family:sans-serif;margin:2em auto;max-width:60em;color:#222}\
+table{border-collapse:collapse;margin:1em 0}\
+th,td{border:1px solid #ccc;padding:.25em .5em;text-align:left}\
+td.number{text-align:right}\
+svg{display:block;margin:1em 0;max-width:100%;height:auto}";
+
+impl super::Report<'_> {
+    /// Renders this report as a single HTML page.
+    ///
+    /// The page embeds its style sheet and charts as inline SVG such that it
+    /// does not need any external assets and can be attached to an email.
+    pub fn to_html(&self) -> String {
+        let metric = self.configuration.loc_metric;
+        let loc = format!("LOC ({})", metric);
+        let mut html = format!(
+            "<!DOCTYPE html><html lang=\"en\"><head><meta charset=\"utf-8\"><title>Commit Analysis</title><style>{}</style></head><body><h1>Commit Analysis</h1>",
+            STYLE
+        );
+
+        table(
+            &mut html,
+            &["Total", "Value"],
+            self.totals.rows(metric),
+            &[1],
+        );
+
+        html.push_str("<h2>Activity</h2>");
+        let size = Size::default();
+        html.push_str(&svg::bars(
+            "Hours per day",
+            &self.daily_series(|d| d.hours),
+            size,
+        ));
+        html.push_str(&svg::bars(
+            "Commits per day",
+            &self.daily_series(|d| d.commits as f64),
+            size,
+        ));
+        html.push_str(&svg::line(
+            &format!("{} per day", loc),
+            &self.daily_series(|d| d.loc.get(metric) as f64),
+            size,
+        ));
+
+        html.push_str("<h2>Authors</h2>");
+        let authors: Vec<(String, f64)> = self
+            .authors
+            .iter()
+            .map(|a| (a.name().to_owned(), a.hours()))
+            .collect();
+        html.push_str(&svg::horizontal_bars(
+            "Hours per author",
+            &authors,
+            Size {
+                height: (80 + 24 * authors.len()) as u32,
+                ..size
+            },
+        ));
+        table(
+            &mut html,
+            &self.author_header(&loc),
+            self.author_rows(metric),
+            &[1, 2, 3, 4, 5, 6],
+        );
+
+        html.push_str("<h2>Periods</h2>");
+        table(
+            &mut html,
+            &["Period", "Start", "End", "Hours", "Commits", &loc],
+            self.periods.iter().map(|p| {
+                vec![
+                    p.period.name().to_owned(),
+                    p.period.start().to_string(),
+                    p.period.end().to_string(),
+                    format!("{:.2}", p.hours),
+                    p.commits.to_string(),
+                    p.loc.get(metric).to_string(),
+                ]
+            }),
+            &[3, 4, 5],
+        );
+
+        if let (Some(shares), Some(level)) = (&self.time_shares, self.configuration.time_by) {
+            html.push_str("<h2>Time</h2>");
+            from_table(&mut html, &TimeShare::to_table(shares, level));
+        }
+
+        if let Some(tickets) = &self.tickets {
+            html.push_str("<h2>Tickets</h2>");
+            from_table(&mut html, &Ticket::to_table(tickets));
+        }
+
+        if let Some(types) = &self.types {
+            html.push_str("<h2>Types</h2>");
+            from_table(&mut html, &types.to_table());
+        }
+
+        if let Some(languages) = &self.languages {
+            html.push_str("<h2>Languages</h2>");
+            from_table(&mut html, &languages.to_table());
+        }
+
+        if let Some(hotspots) = &self.hotspots {
+            html.push_str("<h2>Hotspots</h2>");
+            for table in hotspots.to_tables() {
+                from_table(&mut html, &table);
+            }
+        }
+
+        if let Some(ownership) = &self.ownership {
+            html.push_str("<h2>Ownership</h2>");
+            for table in ownership.to_tables() {
+                from_table(&mut html, &table);
+            }
+        }
+
+        if let Some(coupling) = &self.coupling {
+            html.push_str("<h2>Coupling</h2>");
+            from_table(&mut html, &Coupling::to_table(coupling));
+        }
+
+        if let Some(grouping) = &self.groups {
+            html.push_str("<h2>Groups</h2>");
+            from_table(&mut html, &grouping.to_table());
+        }
+
+        html.push_str("<h2>Punch Card</h2>");
+        for (title, value) in [
+            ("Commits per weekday and hour", PunchCardValue::Commits),
+            (
+                "Working minutes per weekday and hour",
+                PunchCardValue::Minutes,
+            ),
+        ] {
+            let rows: Vec<(String, [f64; 24])> = self
+                .punch_card
+                .weekdays()
+                .iter()
+                .map(|w| (w.weekday().to_string(), w.values(value)))
+                .collect();
+
+            html.push_str(&svg::punch_card(title, &rows, size));
+        }
+
+        html.push_str("<h2>Sessions</h2>");
+        table(
+            &mut html,
+            &[
+                "Start", "End", "Hours", "Authors", "Commits", &loc, "Subjects",
+            ],
+            self.sessions.iter().map(|s| {
+                vec![
+                    s.start.format("%Y-%m-%d %H:%M").to_string(),
+                    s.end.format("%Y-%m-%d %H:%M").to_string(),
+                    format!("{:.2}", s.hours),
+                    s.authors.join(", "),
+                    s.commits.to_string(),
+                    s.loc.get(metric).to_string(),
+                    s.subjects.join("; "),
+                ]
+            }),
+            &[2, 4, 5],
+        );
+
+        if !self.warnings.is_empty() {
+            html.push_str("<h2>Warnings</h2><ul>");
+            for warning in &self.warnings {
+                let _ = write!(html, "<li>{}</li>", escape(warning));
+            }
+            html.push_str("</ul>");
+        }
+
+        html.push_str("</body></html>\n");
+        html
+    }
+
+    /// A value per calendar day, including the days without any activity.
+    fn daily_series<F>(&self, value: F) -> Vec<(String, f64)>
+    where
+        F: Fn(&super::PeriodSummary) -> f64,
+    {
+        let days: std::collections::BTreeMap<chrono::NaiveDate, &super::PeriodSummary> = self
+            .days
+            .iter()
+            .map(|day| (day.period.start(), day))
+            .collect();
+
+        svg::daily(&days)
+            .map(|(date, day)| (date.to_string(), day.map_or(0.0, |day| value(day))))
+            .collect()
+    }
+}
+
+/// Appends the given table as an HTML table, keeping the alignment of its
+/// columns.
+fn from_table(html: &mut String, table: &Table) {
+    let numbers: Vec<usize> = table
+        .alignments()
+        .enumerate()
+        .filter(|(_, alignment)| *alignment == Alignment::Right)
+        .map(|(index, _)| index)
+        .collect();
+
+    self::table(
+        html,
+        &table.header().collect::<Vec<_>>(),
+        table.rows().iter().cloned(),
+        &numbers,
+    );
+}
+
+/// Appends an HTML table, aligning the given columns to the right.
+fn table<I>(html: &mut String, header: &[&str], rows: I, numbers: &[usize])
+where
+    I: IntoIterator<Item = Vec<String>>,
+{
+    html.push_str("<table><tr>");
+    for title in header {
+        let _ = write!(html, "<th>{}</th>", escape(title));
+    }
+    html.push_str("</tr>");
+
+    for row in rows {
+        html.push_str("<tr>");
+        for (index, cell) in row.iter().enumerate() {
+            let class = if numbers.contains(&index) {
+                " class=\"number\""
+            } else {
+                ""
+            };
+
+            let _ = write!(html, "<td{}>{}</td>", class, escape(cell));
+        }
+        html.push_str("</tr>");
+    }
+
+    html.push_str("</table>");
+}
@@ -0,0 +1,166 @@
+//! The rendering of reports as Markdown.
+
+use std::fmt::Write;
+
+use crate::{
+    attribution::TimeShare,
+    coupling::Coupling,
+    table::{Alignment, Table},
+    tickets::Ticket,
+};
+
+impl super::Report<'_> {
+    /// Renders this report as a Markdown document.
+    ///
+    /// The document consists of the headline numbers and a table per author,
+    /// period and session, ready to be pasted into reviews or updates.
+    pub fn to_markdown(&self) -> String {
+        let metric = self.configuration.loc_metric;
+        let loc = format!("LOC ({})", metric);
+        let mut markdown = String::from("# Commit Analysis\n\n");
+
+        table(
+            &mut markdown,
+            &["Total", "Value"],
+            "|:---|---:|",
+            self.totals.rows(metric),
+        );
+
+        markdown.push_str("\n## Authors\n\n");
+        table(
+            &mut markdown,
+            &self.author_header(&loc),
+            "|:---|---:|---:|---:|---:|---:|---:|:---|:---|",
+            self.author_rows(metric),
+        );
+
+        markdown.push_str("\n## Periods\n\n");
+        table(
+            &mut markdown,
+            &["Period", "Start", "End", "Hours", "Commits", &loc],
+            "|:---|:---|:---|---:|---:|---:|",
+            self.periods.iter().map(|p| {
+                vec![
+                    p.period.name().to_owned(),
+                    p.period.start().to_string(),
+                    p.period.end().to_string(),
+                    format!("{:.2}", p.hours),
+                    p.commits.to_string(),
+                    p.loc.get(metric).to_string(),
+                ]
+            }),
+        );
+
+        if let (Some(shares), Some(level)) = (&self.time_shares, self.configuration.time_by) {
+            markdown.push_str("\n## Time\n\n");
+            from_table(&mut markdown, &TimeShare::to_table(shares, level));
+        }
+
+        if let Some(tickets) = &self.tickets {
+            markdown.push_str("\n## Tickets\n\n");
+            from_table(&mut markdown, &Ticket::to_table(tickets));
+        }
+
+        if let Some(types) = &self.types {
+            markdown.push_str("\n## Types\n\n");
+            from_table(&mut markdown, &types.to_table());
+        }
+
+        if let Some(languages) = &self.languages {
+            markdown.push_str("\n## Languages\n\n");
+            from_table(&mut markdown, &languages.to_table());
+        }
+
+        if let Some(hotspots) = &self.hotspots {
+            markdown.push_str("\n## Hotspots\n");
+            for table in hotspots.to_tables() {
+                markdown.push('\n');
+                from_table(&mut markdown, &table);
+            }
+        }
+
+        if let Some(ownership) = &self.ownership {
+            markdown.push_str("\n## Ownership\n");
+            for table in ownership.to_tables() {
+                markdown.push('\n');
+                from_table(&mut markdown, &table);
+            }
+        }
+
+        if let Some(coupling) = &self.coupling {
+            markdown.push_str("\n## Coupling\n\n");
+            from_table(&mut markdown, &Coupling::to_table(coupling));
+        }
+
+        if let Some(grouping) = &self.groups {
+            markdown.push_str("\n## Groups\n\n");
+            from_table(&mut markdown, &grouping.to_table());
+        }
+
+        markdown.push_str("\n## Sessions\n\n");
+        table(
+            &mut markdown,
+            &[
+                "Start", "End", "Hours", "Authors", "Commits", &loc, "Subjects",
+            ],
+            "|:---|:---|---:|:---|---:|---:|:---|",
+            self.sessions.iter().map(|s| {
+                vec![
+                    s.start.format("%Y-%m-%d %H:%M").to_string(),
+                    s.end.format("%Y-%m-%d %H:%M").to_string(),
+                    format!("{:.2}", s.hours),
+                    s.authors.join(", "),
+                    s.commits.to_string(),
+                    s.loc.get(metric).to_string(),
+                    s.subjects.join("; "),
+                ]
+            }),
+        );
+
+        if !self.warnings.is_empty() {
+            markdown.push_str("\n## Warnings\n\n");
+            for warning in &self.warnings {
+                let _ = writeln!(markdown, "- {}", warning);
+            }
+        }
+
+        markdown
+    }
+}
+
+/// Appends the given table as a Markdown table, keeping the alignment of its
+/// columns.
+fn from_table(markdown: &mut String, table: &Table) {
+    let alignment: String = table
+        .alignments()
+        .map(|alignment| match alignment {
+            Alignment::Left => "|:---",
+            Alignment::Right => "|---:",
+        })
+        .chain(["|"])
+        .collect();
+
+    self::table(
+        markdown,
+        &table.header().collect::<Vec<_>>(),
+        &alignment,
+        table.rows().iter().cloned(),
+    );
+}
+
+/// Appends a Markdown table.
+fn table<I>(markdown: &mut String, header: &[&str], alignment: &str, rows: I)
+where
+    I: IntoIterator<Item = Vec<String>>,
+{
+    let _ = writeln!(markdown, "| {} |", header.join(" | "));
+    let _ = writeln!(markdown, "{}", alignment);
+
+    for row in rows {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|cell| cell.replace('|', "\\|").replace('\n', " "))
+            .collect();
+        let _ = writeln!(markdown, "| {} |", cells.join(" | "));
+    }
+}
@@ -0,0 +1,213 @@
+//! The detection of work sessions.
+//!
+//! A work session is a maximal run of consecutive commits in which no two
+//! neighbours are further apart than the configured duration. The estimated
+//! working time is the sum of all session durations.
+
+/// The number of commit subjects to show when summarising a session.
+pub const SUBJECT_COUNT: usize = 3;
+
+/// A period of continuous work.
+#[derive(Debug)]
+pub struct Session<'a> {
+    /// The commits which belong to this session, in chronological order.
+    commits: Vec<&'a crate::Commit>,
+
+    /// The time spent working during this session.
+    duration: chrono::Duration,
+}
+
+impl<'a> Session<'a> {
+    /// Groups the given commits into work sessions.
+    ///
+    /// The commits are expected in chronological order, i.e. reversed in
+    /// comparison to the output of `git log`. Two neighbouring commits belong
+    /// to the same session if they are at most `max_gap` hours apart.
+    pub fn detect<I>(commits: I, max_gap: u32) -> Vec<Self>
+    where
+        I: IntoIterator<Item = &'a crate::Commit>,
+    {
+        let mut sessions: Vec<Self> = vec![];
+
+        for commit in commits {
+            let previous = sessions.last().map(Self::last);
+
+            match (Gap::between(previous, commit, max_gap), sessions.last_mut()) {
+                (Gap::Added(diff), Some(session)) => {
+                    session.duration = session.duration + diff;
+                    session.commits.push(commit);
+                }
+                _ => sessions.push(Self::new(commit)),
+            }
+        }
+
+        sessions
+    }
+
+    /// Starts a new session with the given commit.
+    fn new(commit: &'a crate::Commit) -> Self {
+        Self {
+            commits: vec![commit],
+            duration: chrono::Duration::zero(),
+        }
+    }
+
+    /// The distinct names of the authors who contributed to this session.
+    pub fn authors(&self) -> Vec<&str> {
+        let mut authors = vec![];
+
+        for commit in &self.commits {
+            let name = commit.author().name();
+
+            if !authors.contains(&name) {
+                authors.push(name);
+            }
+        }
+
+        authors
+    }
+
+    /// The getter method for the field `commits` of the corresponding struct.
+    pub fn commits(&self) -> &[&'a crate::Commit] {
+        &self.commits
+    }
+
+    /// The getter method for the field `duration` of the corresponding struct.
+    pub fn duration(&self) -> chrono::Duration {
+        self.duration
+    }
+
+    /// The date of the last commit of this session.
+    pub fn end(&self) -> &chrono::DateTime<chrono::FixedOffset> {
+        self.last().date()
+    }
+
+    /// The last commit of this session.
+    fn last(&self) -> &'a crate::Commit {
+        self.commits[self.commits.len() - 1]
+    }
+
+    /// The spans of time spent working on the individual commits.
+    ///
+    /// The time spent on a commit is the gap since its predecessor within the
+    /// same session. Hence, the first commit of a session does not have a span.
+    pub fn intervals(&self) -> impl Iterator<Item = Interval<'a>> + '_ {
+        self.commits.windows(2).map(|pair| Interval {
+            commit: pair[1],
+            start: *pair[0].date(),
+        })
+    }
+
+    /// The inserted and deleted lines of all commits of this session.
+    pub fn loc(&self, filter: &crate::Filter) -> crate::loc::LocStats {
+        self.commits.iter().map(|c| c.loc_stats(filter)).sum()
+    }
+
+    /// The date of the first commit of this session.
+    pub fn start(&self) -> &chrono::DateTime<chrono::FixedOffset> {
+        self.commits[0].date()
+    }
+
+    /// The subjects of the first commits of this session.
+    ///
+    /// At most `SUBJECT_COUNT` subjects are returned.
+    pub fn subjects(&self) -> Vec<&str> {
+        self.commits
+            .iter()
+            .take(SUBJECT_COUNT)
+            .map(|c| c.subject())
+            .collect()
+    }
+}
+
+/// The span of time spent working on a certain commit.
+#[derive(Clone, Copy, Debug)]
+pub struct Interval<'a> {
+    /// The commit which concluded this span.
+    commit: &'a crate::Commit,
+
+    /// The date of the previous commit.
+    start: chrono::DateTime<chrono::FixedOffset>,
+}
+
+impl<'a> Interval<'a> {
+    /// The getter method for the field `commit` of the corresponding struct.
+    pub fn commit(&self) -> &'a crate::Commit {
+        self.commit
+    }
+
+    /// The time spent working during this span.
+    pub fn duration(&self) -> chrono::Duration {
+        *self.end() - self.start
+    }
+
+    /// The date of the commit which concluded this span.
+    pub fn end(&self) -> &chrono::DateTime<chrono::FixedOffset> {
+        self.commit.date()
+    }
+
+    /// The getter method for the field `start` of the corresponding struct.
+    pub fn start(&self) -> &chrono::DateTime<chrono::FixedOffset> {
+        &self.start
+    }
+}
+
+/// The way the time between two neighbouring commits is accounted for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Gap {
+    /// The gap was short enough and counts as time spent working.
+    Added(chrono::Duration),
+
+    /// The gap was too long, the later commit starts a new session.
+    Broken(chrono::Duration),
+
+    /// There is no previous commit, the commit starts the first session.
+    First,
+}
+
+impl Gap {
+    /// Classifies the gap between the given commit and its predecessor.
+    ///
+    /// The gap counts as working time if its number of whole hours does not
+    /// exceed `max_gap`.
+    pub fn between(previous: Option<&crate::Commit>, commit: &crate::Commit, max_gap: u32) -> Self {
+        let Some(previous) = previous else {
+            return Self::First;
+        };
+        let diff = *commit.date() - *previous.date();
+
+        if diff.num_hours() <= max_gap as i64 {
+            Self::Added(diff)
+        } else {
+            Self::Broken(diff)
+        }
+    }
+}
+
+/// Formats a duration as hours and minutes, e.g. `2h 05m` or `-0h 30m`.
+pub fn format_duration(duration: chrono::Duration) -> String {
+    let sign = if duration.num_minutes() < 0 { "-" } else { "" };
+    let minutes = duration.num_minutes().abs();
+
+    format!("{}{}h {:02}m", sign, minutes / 60, minutes % 60)
+}
+
+/// Converts a duration into fractional hours.
+pub fn hours(duration: chrono::Duration) -> f64 {
+    duration.num_seconds() as f64 / 3600.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_duration;
+
+    #[test]
+    fn format_duration_keeps_the_sign() {
+        let format = |minutes| format_duration(chrono::Duration::minutes(minutes));
+
+        assert_eq!(format(125), "2h 05m");
+        assert_eq!(format(0), "0h 00m");
+        assert_eq!(format(-30), "-0h 30m");
+        assert_eq!(format(-90), "-1h 30m");
+    }
+}
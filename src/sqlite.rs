@@ -0,0 +1,265 @@
+//! The export of all parsed information into a SQLite database.
+//!
+//! The database is normalised such that arbitrary questions can be answered
+//! with SQL instead of yet another command line option. It contains every
+//! parsed commit, regardless of the filters, and the sessions detected from
+//! the matching ones. Dates are stored in UTC as `YYYY-MM-DD HH:MM:SS` such
+//! that they sort correctly and work with SQLite's date functions.
+
+use std::collections::HashMap;
+
+use crate::{session::hours, session::Session, Commit, ParseWarning};
+
+/// The schema of the database.
+const SCHEMA: &str = "
+CREATE TABLE authors (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    email TEXT NOT NULL,
+    UNIQUE (name, email)
+);
+
+CREATE TABLE sessions (
+    id INTEGER PRIMARY KEY,
+    start TEXT NOT NULL,
+    end TEXT NOT NULL,
+    hours REAL NOT NULL,
+    commits INTEGER NOT NULL
+);
+
+CREATE TABLE commits (
+    id INTEGER PRIMARY KEY,
+    hash TEXT NOT NULL UNIQUE,
+    author_id INTEGER NOT NULL REFERENCES authors (id),
+    date TEXT NOT NULL,
+    utc_offset INTEGER NOT NULL,
+    merge TEXT,
+    subject TEXT NOT NULL,
+    type TEXT,
+    scope TEXT,
+    breaking INTEGER,
+    message TEXT NOT NULL,
+    session_id INTEGER REFERENCES sessions (id),
+    hours REAL
+);
+
+CREATE TABLE file_changes (
+    id INTEGER PRIMARY KEY,
+    commit_id INTEGER NOT NULL REFERENCES commits (id),
+    old_path TEXT NOT NULL,
+    new_path TEXT NOT NULL,
+    added INTEGER,
+    removed INTEGER
+);
+
+CREATE TABLE parse_errors (
+    id INTEGER PRIMARY KEY,
+    line INTEGER NOT NULL,
+    message TEXT NOT NULL
+);
+
+CREATE INDEX commits_date ON commits (date);
+CREATE INDEX commits_author ON commits (author_id);
+CREATE INDEX file_changes_commit ON file_changes (commit_id);
+CREATE INDEX file_changes_path ON file_changes (new_path);
+CREATE INDEX sessions_start ON sessions (start);
+";
+
+/// Writes the given commits, sessions and warnings into a new database at the
+/// given path.
+///
+/// The database is written to a temporary file next to the given path first,
+/// which replaces any existing file only on success. The commits need to be
+/// in chronological order, the sessions need to consist of some of them.
+/// Commits outside of any session, e.g. due to the filters, have no session
+/// and no working time. Commits appearing several times, e.g. in concatenated
+/// logs, are only written once.
+pub fn export(
+    path: &std::path::Path,
+    commits: &[&Commit],
+    sessions: &[Session],
+    warnings: &[ParseWarning],
+) -> Result<(), ExportError> {
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    let temporary = std::path::PathBuf::from(temporary);
+
+    if temporary.exists() {
+        std::fs::remove_file(&temporary).map_err(ExportError::Io)?;
+    }
+    if let Err(error) = write(&temporary, commits, sessions, warnings) {
+        std::fs::remove_file(&temporary).ok();
+        return Err(ExportError::Database(error));
+    }
+
+    std::fs::rename(&temporary, path).map_err(ExportError::Io)
+}
+
+/// The set of errors which may occur.
+#[derive(Debug)]
+pub enum ExportError {
+    /// Writing the database was not possible.
+    Database(rusqlite::Error),
+
+    /// Replacing the existing file was not possible.
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Database(error) => write!(f, "{}", error),
+            Self::Io(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+/// Writes the given commits, sessions and warnings into a new database at the
+/// given path, see `export`.
+fn write(
+    path: &std::path::Path,
+    commits: &[&Commit],
+    sessions: &[Session],
+    warnings: &[ParseWarning],
+) -> rusqlite::Result<()> {
+    let mut connection = rusqlite::Connection::open(path)?;
+    let transaction = connection.transaction()?;
+    transaction.execute_batch(SCHEMA)?;
+
+    let mut work: HashMap<&str, (i64, f64)> = HashMap::new();
+    {
+        let mut insert = transaction
+            .prepare("INSERT INTO sessions (start, end, hours, commits) VALUES (?, ?, ?, ?)")?;
+
+        for session in sessions {
+            let id = insert.insert(rusqlite::params![
+                utc(session.start()),
+                utc(session.end()),
+                hours(session.duration()),
+                session.commits().len(),
+            ])?;
+
+            for commit in session.commits() {
+                work.insert(commit.commit(), (id, 0.0));
+            }
+            for interval in session.intervals() {
+                work.get_mut(interval.commit().commit()).unwrap().1 += hours(interval.duration());
+            }
+        }
+    }
+
+    {
+        let mut insert_author =
+            transaction.prepare("INSERT OR IGNORE INTO authors (name, email) VALUES (?, ?)")?;
+        let mut select_author =
+            transaction.prepare("SELECT id FROM authors WHERE name = ? AND email = ?")?;
+        let mut insert_commit = transaction.prepare(
+            "INSERT OR IGNORE INTO commits
+                (hash, author_id, date, utc_offset, merge, subject, type, scope, breaking,
+                 message, session_id, hours)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )?;
+        let mut insert_change = transaction.prepare(
+            "INSERT INTO file_changes (commit_id, old_path, new_path, added, removed)
+             VALUES (?, ?, ?, ?, ?)",
+        )?;
+
+        for commit in commits {
+            let author = commit.author();
+            insert_author.execute([author.name(), author.email()])?;
+            let author_id: i64 =
+                select_author.query_row([author.name(), author.email()], |row| row.get(0))?;
+            let (session_id, hours) = match work.get(commit.commit()) {
+                Some((id, hours)) => (Some(*id), Some(*hours)),
+                None => (None, None),
+            };
+
+            let header = commit.conventional();
+            let inserted = insert_commit.execute(rusqlite::params![
+                commit.commit(),
+                author_id,
+                utc(commit.date()),
+                commit.date().offset().local_minus_utc(),
+                commit.merge(),
+                commit.subject(),
+                header.map(|h| h.kind()),
+                header.and_then(|h| h.scope()),
+                header.map(|h| h.breaking()),
+                commit.message(),
+                session_id,
+                hours,
+            ])?;
+            if inserted == 0 {
+                continue;
+            }
+            let commit_id = transaction.last_insert_rowid();
+
+            for loc in commit.locs() {
+                insert_change.execute(rusqlite::params![
+                    commit_id,
+                    loc.old_path(),
+                    loc.new_path(),
+                    loc.added(),
+                    loc.removed(),
+                ])?;
+            }
+        }
+    }
+
+    {
+        let mut insert =
+            transaction.prepare("INSERT INTO parse_errors (line, message) VALUES (?, ?)")?;
+
+        for warning in warnings {
+            insert.execute(rusqlite::params![
+                warning.line(),
+                warning.error().to_string()
+            ])?;
+        }
+    }
+
+    transaction.commit()
+}
+
+/// Formats the given date in UTC as understood by SQLite.
+fn utc(date: &chrono::DateTime<chrono::FixedOffset>) -> String {
+    date.with_timezone(&chrono::Utc)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    const LOG: &str = "commit 1111111111111111111111111111111111111111
+Author: Alice <alice@example.com>
+Date:   Wed Jun 1 09:00:00 2022 +0000
+
+    First commit
+
+1	0	README.md
+";
+
+    #[test]
+    fn export_skips_duplicate_commits_and_replaces_the_file() {
+        let (commits, warnings) = crate::Commit::parse_log(&format!("{}\n{}", LOG, LOG));
+        let commits: Vec<_> = commits.iter().collect();
+        let path = std::env::temp_dir().join(format!("commit-analyzer-{}.db", std::process::id()));
+        std::fs::write(&path, "existing").unwrap();
+
+        super::export(&path, &commits, &[], &warnings).unwrap();
+
+        let connection = rusqlite::Connection::open(&path).unwrap();
+        let count = |table: &str| -> i64 {
+            connection
+                .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+                    row.get(0)
+                })
+                .unwrap()
+        };
+        assert_eq!((count("commits"), count("file_changes")), (1, 1));
+        drop(connection);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,623 @@
+//! Simple charts rendered as SVG.
+//!
+//! The charts are self-contained and do not need any external assets, such
+//! that they can be embedded into HTML reports or exported as images, e.g. for
+//! wiki pages.
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use chrono::Datelike;
+
+/// The margin around the plot area in pixels.
+const MARGIN: f64 = 40.0;
+
+/// The colour of the plotted data.
+const COLOUR: &str = "#3b82f6";
+
+/// The colours of several data series, e.g. in a stacked area chart.
+const PALETTE: [&str; 8] = [
+    "#3b82f6", "#f59e0b", "#10b981", "#ef4444", "#8b5cf6", "#ec4899", "#14b8a6", "#6b7280",
+];
+
+/// The colours of a heatmap, from no activity to the maximum.
+const HEAT: [&str; 5] = ["#ebedf0", "#9be9a8", "#40c463", "#30a14e", "#216e39"];
+
+/// The size of a chart in pixels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Size {
+    /// The height.
+    pub height: u32,
+
+    /// The width.
+    pub width: u32,
+}
+
+impl Size {
+    /// The smallest size leaving a plot area in every chart.
+    ///
+    /// The horizontal bars need the most width, since a quarter of it is
+    /// reserved for the labels besides three margins. The punch card needs the
+    /// most height, since the hours are labelled below the plot.
+    pub const MIN: Self = Self {
+        height: (2.5 * MARGIN) as u32 + 1,
+        width: (4.0 * MARGIN) as u32 + 1,
+    };
+}
+
+impl Default for Size {
+    fn default() -> Self {
+        Self {
+            height: 240,
+            width: 720,
+        }
+    }
+}
+
+impl std::str::FromStr for Size {
+    type Err = SizeParseError;
+
+    fn from_str(size: &str) -> Result<Self, Self::Err> {
+        let (width, height) = size
+            .split_once('x')
+            .ok_or(SizeParseError::SeparatorMissing)?;
+        let parse = |number: &str| {
+            number
+                .trim()
+                .parse::<std::num::NonZeroU32>()
+                .map(std::num::NonZeroU32::get)
+                .map_err(SizeParseError::NumberFailed)
+        };
+
+        let size = Self {
+            height: parse(height)?,
+            width: parse(width)?,
+        };
+
+        if size.height < Self::MIN.height || size.width < Self::MIN.width {
+            Err(SizeParseError::PlotAreaEmpty)
+        } else {
+            Ok(size)
+        }
+    }
+}
+
+/// The set of errors which may occur.
+#[derive(Debug)]
+pub enum SizeParseError {
+    /// The width or the height is not a positive number.
+    NumberFailed(std::num::ParseIntError),
+
+    /// The size is too small to leave a plot area between the margins.
+    PlotAreaEmpty,
+
+    /// The width and the height are not separated by an `x`.
+    SeparatorMissing,
+}
+
+impl std::fmt::Display for SizeParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NumberFailed(error) => write!(f, "invalid dimension, {}", error),
+            Self::PlotAreaEmpty => write!(
+                f,
+                "no room to plot, expected at least {}x{}",
+                Size::MIN.width,
+                Size::MIN.height
+            ),
+            Self::SeparatorMissing => write!(f, "expected the width and height, e.g. 720x240"),
+        }
+    }
+}
+
+impl std::error::Error for SizeParseError {}
+
+/// The charts which can be exported as SVG files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Chart {
+    /// A bar per period with the estimated hours.
+    Hours,
+
+    /// A line with the cumulative net LOC diff per day.
+    Loc,
+
+    /// A stacked area per author with the estimated hours per period.
+    Authors,
+
+    /// A calendar heatmap of the estimated hours per day.
+    Heatmap,
+}
+
+impl Chart {
+    /// The name of the file to export this chart to.
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Self::Hours => "hours.svg",
+            Self::Loc => "loc.svg",
+            Self::Authors => "authors.svg",
+            Self::Heatmap => "heatmap.svg",
+        }
+    }
+
+    /// Renders this chart of the given daily activity.
+    ///
+    /// The periods of the hours and the authors are determined by the given
+    /// calendar, the heatmap's rows begin with the given first day of a week.
+    pub fn render(
+        self,
+        days: &BTreeMap<chrono::NaiveDate, crate::activity::Activity>,
+        calendar: &crate::period::Calendar,
+        week_start: chrono::Weekday,
+        size: Size,
+    ) -> String {
+        let hours = |activity: &crate::activity::Activity| crate::session::hours(activity.work());
+
+        match self {
+            Self::Hours => {
+                let points: Vec<(String, f64)> = calendar
+                    .aggregate(days)
+                    .iter()
+                    .map(|(period, activity)| (period.name().to_owned(), hours(activity)))
+                    .collect();
+
+                bars("Hours per period", &points, size)
+            }
+            Self::Loc => {
+                let mut sum = 0;
+                let points: Vec<(String, f64)> = daily(days)
+                    .map(|(date, activity)| {
+                        sum += activity.map_or(0, |a| a.loc().net());
+                        (date.to_string(), sum as f64)
+                    })
+                    .collect();
+
+                line("Cumulative LOC", &points, size)
+            }
+            Self::Authors => {
+                let periods = calendar.aggregate(days);
+                let labels: Vec<String> = periods
+                    .keys()
+                    .map(|period| period.name().to_owned())
+                    .collect();
+                let names: std::collections::BTreeSet<&String> = periods
+                    .values()
+                    .flat_map(|activity| activity.authors().keys())
+                    .collect();
+                let series: Vec<(String, Vec<f64>)> = names
+                    .into_iter()
+                    .map(|name| {
+                        let values = periods
+                            .values()
+                            .map(|activity| {
+                                let work = activity.authors().get(name).copied();
+                                crate::session::hours(work.unwrap_or_else(chrono::Duration::zero))
+                            })
+                            .collect();
+
+                        (name.clone(), values)
+                    })
+                    .collect();
+
+                stacked_area("Hours per author", &labels, &series, size)
+            }
+            Self::Heatmap => {
+                let values = days
+                    .iter()
+                    .map(|(date, activity)| (*date, hours(activity)))
+                    .collect();
+
+                heatmap("Hours per day", &values, week_start, size)
+            }
+        }
+    }
+}
+
+/// Escapes the characters with a special meaning in XML.
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Draws a vertical bar per data point.
+pub fn bars(title: &str, points: &[(String, f64)], size: Size) -> String {
+    let mut svg = open(title, size);
+    let (plot_width, plot_height) = plot_area(size);
+    let (min, max) = range(points.iter().map(|(_, v)| *v));
+    let scale = |value: f64| MARGIN + plot_height * (max - value) / (max - min);
+    let width = plot_width / points.len().max(1) as f64;
+
+    axes(&mut svg, size, min, max, points);
+    for (index, (label, value)) in points.iter().enumerate() {
+        let (top, bottom) = if *value < 0.0 {
+            (scale(0.0), scale(*value))
+        } else {
+            (scale(*value), scale(0.0))
+        };
+
+        let _ = write!(
+            svg,
+            r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}"><title>{}: {}</title></rect>"#,
+            MARGIN + index as f64 * width + width * 0.1,
+            top,
+            width * 0.8,
+            bottom - top,
+            COLOUR,
+            escape(label),
+            format_value(*value)
+        );
+    }
+
+    close(svg)
+}
+
+/// Draws a horizontal bar per data point, labelled on the left.
+pub fn horizontal_bars(title: &str, points: &[(String, f64)], size: Size) -> String {
+    let mut svg = open(title, size);
+    let label_width = size.width as f64 / 4.0;
+    let plot_width = size.width as f64 - label_width - 3.0 * MARGIN;
+    let (_, max) = range(points.iter().map(|(_, v)| *v));
+    let height = (size.height as f64 - 2.0 * MARGIN) / points.len().max(1) as f64;
+
+    for (index, (label, value)) in points.iter().enumerate() {
+        let y = MARGIN + index as f64 * height;
+
+        let _ = write!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="end" dominant-baseline="middle">{}</text><rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}"><title>{}: {}</title></rect><text x="{:.1}" y="{:.1}" dominant-baseline="middle">{}</text>"#,
+            MARGIN + label_width - 8.0,
+            y + height / 2.0,
+            escape(label),
+            MARGIN + label_width,
+            y + height * 0.1,
+            plot_width * value.max(0.0) / max,
+            height * 0.8,
+            COLOUR,
+            escape(label),
+            format_value(*value),
+            MARGIN + label_width + plot_width * value.max(0.0) / max + 4.0,
+            y + height / 2.0,
+            format_value(*value)
+        );
+    }
+
+    close(svg)
+}
+
+/// Draws a line through the data points.
+pub fn line(title: &str, points: &[(String, f64)], size: Size) -> String {
+    let mut svg = open(title, size);
+    let (plot_width, plot_height) = plot_area(size);
+    let (min, max) = range(points.iter().map(|(_, v)| *v));
+    let step = plot_width / points.len().saturating_sub(1).max(1) as f64;
+    let coordinates: Vec<String> = points
+        .iter()
+        .enumerate()
+        .map(|(index, (_, value))| {
+            format!(
+                "{:.1},{:.1}",
+                MARGIN + index as f64 * step,
+                MARGIN + plot_height * (max - value) / (max - min)
+            )
+        })
+        .collect();
+
+    axes(&mut svg, size, min, max, points);
+    let _ = write!(
+        svg,
+        r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="2"/>"#,
+        coordinates.join(" "),
+        COLOUR
+    );
+    for ((label, value), coordinate) in points.iter().zip(&coordinates) {
+        let (x, y) = coordinate.split_once(',').unwrap_or_default();
+
+        let _ = write!(
+            svg,
+            r#"<circle cx="{}" cy="{}" r="3" fill="{}"><title>{}: {}</title></circle>"#,
+            x,
+            y,
+            COLOUR,
+            escape(label),
+            format_value(*value)
+        );
+    }
+
+    close(svg)
+}
+
+/// Draws a punch card with a row per label and a circle per hour of the day.
+///
+/// The area of a circle is proportional to its value.
+pub fn punch_card(title: &str, rows: &[(String, [f64; 24])], size: Size) -> String {
+    let mut svg = open(title, size);
+    let label_width = MARGIN * 1.5;
+    let width = (size.width as f64 - label_width - 2.0 * MARGIN) / 24.0;
+    let height = (size.height as f64 - 2.5 * MARGIN) / rows.len().max(1) as f64;
+    let radius = width.min(height) * 0.45;
+    let max = rows
+        .iter()
+        .flat_map(|(_, values)| values.iter().copied())
+        .fold(0.0, f64::max);
+
+    for hour in (0..24).step_by(3) {
+        let _ = write!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">{}</text>"#,
+            MARGIN + label_width + (hour as f64 + 0.5) * width,
+            size.height as f64 - MARGIN * 0.5,
+            hour
+        );
+    }
+    for (index, (label, values)) in rows.iter().enumerate() {
+        let y = MARGIN + (index as f64 + 0.5) * height;
+
+        let _ = write!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="end" dominant-baseline="middle">{}</text>"#,
+            MARGIN + label_width - 8.0,
+            y,
+            escape(label)
+        );
+        for (hour, value) in values.iter().enumerate() {
+            if *value <= 0.0 || max <= 0.0 {
+                continue;
+            }
+
+            let _ = write!(
+                svg,
+                r#"<circle cx="{:.1}" cy="{:.1}" r="{:.1}" fill="{}"><title>{} {}:00: {}</title></circle>"#,
+                MARGIN + label_width + (hour as f64 + 0.5) * width,
+                y,
+                radius * (value / max).sqrt(),
+                COLOUR,
+                escape(label),
+                hour,
+                format_value(*value)
+            );
+        }
+    }
+
+    close(svg)
+}
+
+/// Draws stacked areas, one per series, over the given labels.
+///
+/// Every series holds a value per label. The series are listed in a legend
+/// below the chart.
+pub fn stacked_area(
+    title: &str,
+    labels: &[String],
+    series: &[(String, Vec<f64>)],
+    size: Size,
+) -> String {
+    let mut svg = open(title, size);
+    let (plot_width, plot_height) = plot_area(size);
+    let totals: Vec<f64> = (0..labels.len())
+        .map(|index| series.iter().map(|(_, values)| values[index]).sum())
+        .collect();
+    let (min, max) = range(totals.iter().copied());
+    let step = plot_width / labels.len().saturating_sub(1).max(1) as f64;
+    let x = |index: usize| MARGIN + index as f64 * step;
+    let y = |value: f64| MARGIN + plot_height * (max - value) / (max - min);
+    let points: Vec<(String, f64)> = labels.iter().cloned().zip(totals).collect();
+    let mut baseline = vec![0.0; labels.len()];
+    let mut legend = MARGIN;
+
+    axes(&mut svg, size, min, max, &points);
+    for (index, (name, values)) in series.iter().enumerate() {
+        let colour = PALETTE[index % PALETTE.len()];
+        let top: Vec<f64> = baseline.iter().zip(values).map(|(b, v)| b + v).collect();
+        let outline: Vec<String> = (0..labels.len())
+            .map(|i| format!("{:.1},{:.1}", x(i), y(top[i])))
+            .chain(
+                (0..labels.len())
+                    .rev()
+                    .map(|i| format!("{:.1},{:.1}", x(i), y(baseline[i]))),
+            )
+            .collect();
+
+        let _ = write!(
+            svg,
+            r#"<polygon points="{}" fill="{}" fill-opacity="0.8"><title>{}</title></polygon><rect x="{:.1}" y="{:.1}" width="10" height="10" fill="{}"/><text x="{:.1}" y="{:.1}" dominant-baseline="middle">{}</text>"#,
+            outline.join(" "),
+            colour,
+            escape(name),
+            legend,
+            size.height as f64 - 13.0,
+            colour,
+            legend + 14.0,
+            size.height as f64 - 8.0,
+            escape(name)
+        );
+        legend += 24.0 + 7.0 * name.chars().count() as f64;
+        baseline = top;
+    }
+
+    close(svg)
+}
+
+/// Draws a calendar heatmap with a row per weekday and a column per week.
+///
+/// The cells are shaded in four levels relative to the highest value. Days
+/// without a value are drawn as the lowest level.
+pub fn heatmap(
+    title: &str,
+    days: &BTreeMap<chrono::NaiveDate, f64>,
+    week_start: chrono::Weekday,
+    size: Size,
+) -> String {
+    let mut svg = open(title, size);
+    let (first, last) = match (days.keys().next(), days.keys().next_back()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => return close(svg),
+    };
+    let start = crate::period::first_day_of_week(first, week_start);
+    let weeks = (last - start).num_days() / 7 + 1;
+    let label_width = MARGIN;
+    let cell = ((size.width as f64 - label_width - 2.0 * MARGIN) / weeks as f64)
+        .min((size.height as f64 - 2.0 * MARGIN) / 7.0);
+    let max = days.values().copied().fold(0.0, f64::max);
+
+    for weekday in 0..7 {
+        let _ = write!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="end" dominant-baseline="middle">{}</text>"#,
+            MARGIN + label_width - 4.0,
+            MARGIN + (weekday as f64 + 0.5) * cell,
+            (start + chrono::Duration::days(weekday)).format("%a")
+        );
+    }
+    for date in first.iter_days().take_while(|date| *date <= last) {
+        let index = (date - start).num_days();
+        let value = days.get(&date).copied().unwrap_or_default();
+        let level = if value <= 0.0 || max <= 0.0 {
+            0
+        } else {
+            ((value / max * 4.0).ceil() as usize).clamp(1, 4)
+        };
+
+        if date == first || date.day() == 1 {
+            let _ = write!(
+                svg,
+                r#"<text x="{:.1}" y="{:.1}">{}</text>"#,
+                MARGIN + label_width + (index / 7) as f64 * cell,
+                MARGIN - 6.0,
+                date.format("%b")
+            );
+        }
+        let _ = write!(
+            svg,
+            r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}"><title>{}: {}</title></rect>"#,
+            MARGIN + label_width + (index / 7) as f64 * cell + 1.0,
+            MARGIN + (index % 7) as f64 * cell + 1.0,
+            cell - 2.0,
+            cell - 2.0,
+            HEAT[level],
+            date,
+            format_value(value)
+        );
+    }
+
+    close(svg)
+}
+
+/// Draws the axes, the range of values and the first and last label.
+fn axes(svg: &mut String, size: Size, min: f64, max: f64, points: &[(String, f64)]) {
+    let (plot_width, plot_height) = plot_area(size);
+    let zero = MARGIN + plot_height * max / (max - min);
+
+    let _ = write!(
+        svg,
+        r##"<line x1="{m}" y1="{m}" x2="{m}" y2="{b}" stroke="#888"/><line x1="{m}" y1="{z:.1}" x2="{r}" y2="{z:.1}" stroke="#888"/><text x="{t}" y="{m}" text-anchor="end" dominant-baseline="middle">{max}</text><text x="{t}" y="{b}" text-anchor="end" dominant-baseline="middle">{min}</text>"##,
+        m = MARGIN,
+        b = MARGIN + plot_height,
+        r = MARGIN + plot_width,
+        z = zero,
+        t = MARGIN - 4.0,
+        max = format_value(max),
+        min = format_value(min),
+    );
+    if let (Some((first, _)), Some((last, _))) = (points.first(), points.last()) {
+        let _ = write!(
+            svg,
+            r#"<text x="{}" y="{}" dominant-baseline="hanging">{}</text><text x="{}" y="{}" text-anchor="end" dominant-baseline="hanging">{}</text>"#,
+            MARGIN,
+            MARGIN + plot_height + 6.0,
+            escape(first),
+            MARGIN + plot_width,
+            MARGIN + plot_height + 6.0,
+            escape(last)
+        );
+    }
+}
+
+/// Every calendar day from the first to the last of the given ones, together
+/// with its activity, if any.
+pub fn daily<T>(
+    days: &BTreeMap<chrono::NaiveDate, T>,
+) -> impl Iterator<Item = (chrono::NaiveDate, Option<&T>)> {
+    let first = days.keys().next().copied();
+    let last = days.keys().next_back().copied();
+
+    first
+        .into_iter()
+        .flat_map(|first| first.iter_days())
+        .take_while(move |date| Some(*date) <= last)
+        .map(move |date| (date, days.get(&date)))
+}
+
+/// Finishes a chart.
+fn close(mut svg: String) -> String {
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Formats a value with at most two decimal places.
+fn format_value(value: f64) -> String {
+    let formatted = format!("{:.2}", value);
+
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_owned()
+}
+
+/// Starts a chart with the given title.
+fn open(title: &str, size: Size) -> String {
+    format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="12"><text x="{x}" y="20" text-anchor="middle" font-size="14" font-weight="bold">{t}</text>"#,
+        w = size.width,
+        h = size.height,
+        x = size.width / 2,
+        t = escape(title)
+    )
+}
+
+/// The width and height of the area the data is plotted in.
+fn plot_area(size: Size) -> (f64, f64) {
+    (
+        size.width as f64 - 2.0 * MARGIN,
+        size.height as f64 - 2.0 * MARGIN,
+    )
+}
+
+/// The range of values to plot, always including zero.
+fn range(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    let (min, max) = values.fold((0.0_f64, 0.0_f64), |(min, max), value| {
+        (min.min(value), max.max(value))
+    });
+
+    if max - min <= f64::EPSILON {
+        (min, min + 1.0)
+    } else {
+        (min, max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Size;
+
+    #[test]
+    fn parse_size() {
+        assert_eq!(
+            " 640 x 480".parse::<Size>().unwrap(),
+            Size {
+                height: 480,
+                width: 640
+            }
+        );
+        for size in ["0x0", "640x0", "0x480", "640", "640x-1", "x"] {
+            assert!(size.parse::<Size>().is_err(), "{}", size);
+        }
+    }
+
+    #[test]
+    fn size_must_leave_a_plot_area() {
+        assert_eq!("161x101".parse::<Size>().unwrap(), Size::MIN);
+        for size in ["60x50", "160x240", "720x100"] {
+            assert!(size.parse::<Size>().is_err(), "{}", size);
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! A plain text table for terminal output.
+
+/// The alignment of a table column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Alignment {
+    /// Pads the cells on the right.
+    Left,
+
+    /// Pads the cells on the left.
+    Right,
+}
+
+/// A table with aligned columns.
+#[derive(Debug, Default)]
+pub struct Table {
+    /// The column titles and their alignment.
+    header: Vec<(String, Alignment)>,
+
+    /// The table's content.
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Creates a new table with the given columns.
+    pub fn new(header: &[(&str, Alignment)]) -> Self {
+        Self {
+            header: header
+                .iter()
+                .map(|(title, alignment)| ((*title).to_owned(), *alignment))
+                .collect(),
+            rows: vec![],
+        }
+    }
+
+    /// The titles of the columns.
+    pub fn header(&self) -> impl Iterator<Item = &str> {
+        self.header.iter().map(|(title, _)| title.as_str())
+    }
+
+    /// The alignments of the columns.
+    pub fn alignments(&self) -> impl Iterator<Item = Alignment> + '_ {
+        self.header.iter().map(|(_, alignment)| *alignment)
+    }
+
+    /// Appends a row to the table.
+    ///
+    /// Missing cells are left empty, surplus ones are ignored.
+    pub fn push(&mut self, mut row: Vec<String>) {
+        row.resize(self.header.len(), String::new());
+        self.rows.push(row);
+    }
+
+    /// The getter method for the field `rows` of the corresponding struct.
+    pub fn rows(&self) -> &[Vec<String>] {
+        &self.rows
+    }
+}
+
+impl std::fmt::Display for Table {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut widths: Vec<usize> = self
+            .header
+            .iter()
+            .map(|(title, _)| title.chars().count())
+            .collect();
+
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let separator: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+        let header: Vec<String> = self.header().map(str::to_owned).collect();
+
+        for row in [&header, &separator].into_iter().chain(&self.rows) {
+            let mut line = String::new();
+
+            for (index, cell) in row.iter().enumerate() {
+                let padding = " ".repeat(widths[index] - cell.chars().count());
+
+                if index > 0 {
+                    line.push_str("  ");
+                }
+
+                match self.header[index].1 {
+                    Alignment::Left => {
+                        line.push_str(cell);
+                        line.push_str(&padding);
+                    }
+                    Alignment::Right => {
+                        line.push_str(&padding);
+                        line.push_str(cell);
+                    }
+                }
+            }
+
+            writeln!(f, "{}", line.trim_end())?;
+        }
+
+        Ok(())
+    }
+}
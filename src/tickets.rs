@@ -0,0 +1,374 @@
+//! The extraction of ticket references and the working time per ticket.
+//!
+//! Commit messages often reference the tickets of an issue tracker, such as
+//! `PROJ-123`, `#456` or `GH-78`. The references are extracted from the whole
+//! message, including the body and the messages of merge commits, which
+//! usually name the merged branch. The working time of a commit is
+//! attributed to the tickets it references. Commits without a reference
+//! continue the work on the tickets of the previous commit of their session
+//! or, at the beginning of a session, prepare the work on the tickets of the
+//! next one.
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{
+    attribution::Attribution,
+    loc::LocStats,
+    session::Session,
+    table::{Alignment, Table},
+    timezone::Timezone,
+    Commit,
+};
+
+/// The patterns used unless the user configures some.
+///
+/// Project keys need at least two letters, such that e.g. `C-3` or `V-2`
+/// are not mistaken for tickets.
+pub const DEFAULT_PATTERNS: [&str; 2] = [r"\b[A-Z]{2}[A-Z0-9_]*-[0-9]+\b", r"#[0-9]+\b"];
+
+/// The well-known standards which look like ticket keys, e.g. `UTF-8` or
+/// `ISO-8601`, and are ignored by the default patterns.
+pub const STANDARDS: [&str; 13] = [
+    "AES", "CRC", "CVE", "ECMA", "IEEE", "ISO", "PEP", "RFC", "RSA", "SHA", "TLS", "UCS", "UTF",
+];
+
+/// The default patterns, ignoring the well-known standards.
+pub fn default_patterns() -> Vec<Pattern> {
+    DEFAULT_PATTERNS
+        .iter()
+        .map(|pattern| Pattern {
+            regex: regex::Regex::new(pattern).unwrap(),
+            ignored: &STANDARDS,
+        })
+        .collect()
+}
+
+/// A regular expression matching ticket references.
+///
+/// If the expression contains a capture group, the first group is the key of
+/// the ticket. This allows to normalise references, e.g. `(?:GH-|#)([0-9]+)`
+/// treats `GH-78` and `#78` as the same ticket.
+#[derive(Clone, Debug)]
+pub struct Pattern {
+    /// The expression.
+    regex: regex::Regex,
+
+    /// The prefixes of the keys to ignore, up to the first `-`.
+    ignored: &'static [&'static str],
+}
+
+impl Pattern {
+    /// The ticket keys the given message references, with their offsets.
+    fn extract<'a>(&self, message: &'a str) -> Vec<(usize, &'a str)> {
+        self.regex
+            .captures_iter(message)
+            .filter_map(|captures| captures.get(1).or_else(|| captures.get(0)))
+            .filter(|key| {
+                let prefix = key.as_str().split('-').next().unwrap_or_default();
+
+                !self.ignored.contains(&prefix)
+            })
+            .map(|key| (key.start(), key.as_str()))
+            .collect()
+    }
+}
+
+impl std::str::FromStr for Pattern {
+    type Err = regex::Error;
+
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        Ok(Self {
+            regex: regex::Regex::new(pattern)?,
+            ignored: &[],
+        })
+    }
+}
+
+/// The distinct ticket keys the given message references.
+///
+/// The keys are ordered by their first appearance in the message.
+pub fn extract<'a>(message: &'a str, patterns: &[Pattern]) -> Vec<&'a str> {
+    let mut matches: Vec<(usize, &str)> = patterns
+        .iter()
+        .flat_map(|pattern| pattern.extract(message))
+        .collect();
+    matches.sort();
+
+    let mut keys = vec![];
+    for (_, key) in matches {
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+
+    keys
+}
+
+/// The aggregates of a single ticket.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Ticket {
+    /// The key of the ticket, none for the commits of sessions without any
+    /// reference.
+    key: Option<String>,
+
+    /// The number of commits attributed to the ticket.
+    commits: usize,
+
+    /// The inserted and deleted lines of these commits.
+    loc: LocStats,
+
+    /// The working time in hours.
+    hours: f64,
+
+    /// The date of the first commit.
+    first_commit: chrono::DateTime<chrono::FixedOffset>,
+
+    /// The date of the last commit.
+    last_commit: chrono::DateTime<chrono::FixedOffset>,
+}
+
+impl Ticket {
+    /// Attributes the commits of the given sessions to the tickets they
+    /// reference.
+    ///
+    /// The working time is distributed over the commits as configured by the
+    /// given attribution, the time of a commit referencing several tickets is
+    /// split evenly between them. Such a commit counts for each ticket with
+    /// all of its lines. The tickets are sorted by their hours, descending,
+    /// the unreferenced work comes last.
+    pub fn collect(
+        sessions: &[Session],
+        filter: &crate::Filter,
+        timezone: Timezone,
+        attribution: Attribution,
+        patterns: &[Pattern],
+    ) -> Vec<Self> {
+        let mut tickets: HashMap<Option<&str>, Builder> = HashMap::new();
+
+        for session in sessions {
+            let work = attribution.distribute(session, filter);
+            let keys: Vec<Vec<&str>> = session
+                .commits()
+                .iter()
+                .map(|commit| extract(commit.message(), patterns))
+                .collect();
+            let previous = carry(keys.iter());
+            let mut next = carry(keys.iter().rev());
+            next.reverse();
+
+            for ((commit, previous), next) in session.commits().iter().zip(previous).zip(next) {
+                let keys: Vec<Option<&str>> = match (previous, next) {
+                    (keys, _) if !keys.is_empty() => keys.into_iter().map(Some).collect(),
+                    (_, keys) if !keys.is_empty() => keys.into_iter().map(Some).collect(),
+                    _ => vec![None],
+                };
+                let hours = work.get(commit.commit()).map_or(0.0, |(hours, _)| *hours);
+                let date = timezone.convert(commit.date());
+
+                for key in &keys {
+                    tickets
+                        .entry(*key)
+                        .or_insert_with(|| Builder::new(date))
+                        .add(commit, date, hours / keys.len() as f64, filter);
+                }
+            }
+        }
+
+        let mut tickets: Vec<Self> = tickets
+            .into_iter()
+            .map(|(key, builder)| builder.build(key))
+            .collect();
+
+        tickets.sort_by(|a, b| {
+            a.key
+                .is_none()
+                .cmp(&b.key.is_none())
+                .then_with(|| b.hours.total_cmp(&a.hours))
+                .then_with(|| a.key.cmp(&b.key))
+        });
+        tickets
+    }
+
+    /// The getter method for the field `commits` of the corresponding struct.
+    pub fn commits(&self) -> usize {
+        self.commits
+    }
+
+    /// The getter method for the field `first_commit` of the corresponding
+    /// struct.
+    pub fn first_commit(&self) -> &chrono::DateTime<chrono::FixedOffset> {
+        &self.first_commit
+    }
+
+    /// The getter method for the field `hours` of the corresponding struct.
+    pub fn hours(&self) -> f64 {
+        self.hours
+    }
+
+    /// The getter method for the field `key` of the corresponding struct.
+    pub fn key(&self) -> Option<&str> {
+        self.key.as_deref()
+    }
+
+    /// The getter method for the field `last_commit` of the corresponding
+    /// struct.
+    pub fn last_commit(&self) -> &chrono::DateTime<chrono::FixedOffset> {
+        &self.last_commit
+    }
+
+    /// The getter method for the field `loc` of the corresponding struct.
+    pub fn loc(&self) -> LocStats {
+        self.loc
+    }
+
+    /// Converts the given tickets into a table.
+    ///
+    /// The commits without a ticket are listed as `(none)`.
+    pub fn to_table(tickets: &[Self]) -> Table {
+        let mut table = Table::new(&[
+            ("Ticket", Alignment::Left),
+            ("Commits", Alignment::Right),
+            ("Added", Alignment::Right),
+            ("Removed", Alignment::Right),
+            ("Hours", Alignment::Right),
+            ("First Commit", Alignment::Left),
+            ("Last Commit", Alignment::Left),
+        ]);
+
+        for ticket in tickets {
+            table.push(vec![
+                ticket.key().unwrap_or("(none)").to_owned(),
+                ticket.commits().to_string(),
+                ticket.loc().added().to_string(),
+                ticket.loc().removed().to_string(),
+                format!("{:.2}", ticket.hours()),
+                ticket.first_commit().format("%Y-%m-%d").to_string(),
+                ticket.last_commit().format("%Y-%m-%d").to_string(),
+            ]);
+        }
+
+        table
+    }
+}
+
+/// The keys of the last commit with any references, for each of the given
+/// commits' keys.
+fn carry<'k, 'a: 'k, I>(keys: I) -> Vec<Vec<&'a str>>
+where
+    I: Iterator<Item = &'k Vec<&'a str>>,
+{
+    let mut last: &[&str] = &[];
+
+    keys.map(|keys| {
+        if !keys.is_empty() {
+            last = keys;
+        }
+        last.to_vec()
+    })
+    .collect()
+}
+
+/// The partial aggregates of a ticket.
+struct Builder<'a> {
+    /// The hashes of the commits.
+    commits: BTreeSet<&'a str>,
+
+    /// The date of the first commit.
+    first_commit: chrono::DateTime<chrono::FixedOffset>,
+
+    /// The working time in hours.
+    hours: f64,
+
+    /// The date of the last commit.
+    last_commit: chrono::DateTime<chrono::FixedOffset>,
+
+    /// The inserted and deleted lines.
+    loc: LocStats,
+}
+
+impl<'a> Builder<'a> {
+    /// Creates an empty builder for a ticket first worked on at the given
+    /// date.
+    fn new(date: chrono::DateTime<chrono::FixedOffset>) -> Self {
+        Self {
+            commits: BTreeSet::new(),
+            first_commit: date,
+            hours: 0.0,
+            last_commit: date,
+            loc: LocStats::default(),
+        }
+    }
+
+    /// Adds the given commit with its share of the working time.
+    fn add(
+        &mut self,
+        commit: &'a Commit,
+        date: chrono::DateTime<chrono::FixedOffset>,
+        hours: f64,
+        filter: &crate::Filter,
+    ) {
+        if self.commits.insert(commit.commit()) {
+            self.loc += commit.loc_stats(filter);
+        }
+        self.first_commit = self.first_commit.min(date);
+        self.hours += hours;
+        self.last_commit = self.last_commit.max(date);
+    }
+
+    /// Finishes the ticket with the given key.
+    fn build(self, key: Option<&str>) -> Ticket {
+        Ticket {
+            key: key.map(str::to_owned),
+            commits: self.commits.len(),
+            loc: self.loc,
+            hours: self.hours,
+            first_commit: self.first_commit,
+            last_commit: self.last_commit,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{default_patterns, extract, Pattern};
+
+    #[test]
+    fn extract_finds_the_default_keys_in_order() {
+        let patterns = default_patterns();
+
+        assert_eq!(
+            extract("Fix #12 and PROJ-3, see PROJ-3 and GH-78", &patterns),
+            ["#12", "PROJ-3", "GH-78"]
+        );
+        assert_eq!(
+            extract("Merge branch 'feature/AB_2-41-login'", &patterns),
+            ["AB_2-41"]
+        );
+    }
+
+    #[test]
+    fn extract_ignores_standards_and_single_letters() {
+        let patterns = default_patterns();
+
+        assert!(extract(
+            "Use UTF-8, SHA-256 and ISO-8601 as in RFC-3339, not C-3",
+            &patterns
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn extract_uses_the_first_capture_group_as_key() {
+        let patterns: Vec<Pattern> = vec!["(?:GH-|#)([0-9]+)".parse().unwrap()];
+
+        assert_eq!(extract("GH-78 and #78, then #9", &patterns), ["78", "9"]);
+        assert!("(".parse::<Pattern>().is_err());
+    }
+
+    #[test]
+    fn custom_patterns_keep_standards() {
+        let patterns: Vec<Pattern> = vec![r"\bISO-[0-9]+\b".parse().unwrap()];
+
+        assert_eq!(extract("Implement ISO-8601", &patterns), ["ISO-8601"]);
+    }
+}
@@ -0,0 +1,264 @@
+//! The time zone to convert commit dates into.
+//!
+//! By default, every commit date keeps the offset it was recorded with. For a
+//! distributed team, these offsets lead to calendar days which do not line up.
+//! Converting all dates into a common time zone before bucketing them avoids
+//! this.
+
+use chrono::{Offset, TimeZone, Timelike};
+
+/// A time zone to convert commit dates into.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Timezone {
+    /// A fixed offset from UTC, e.g. `+02:00`.
+    Fixed(chrono::FixedOffset),
+
+    /// The time zone of the local machine.
+    Local,
+
+    /// A time zone of the IANA time zone database, e.g. `Europe/Berlin`.
+    Named(chrono_tz::Tz),
+
+    /// The offset each date was recorded with.
+    Original,
+
+    /// Coordinated Universal Time.
+    Utc,
+}
+
+impl Timezone {
+    /// Converts the given date into this time zone.
+    pub fn convert(
+        &self,
+        date: &chrono::DateTime<chrono::FixedOffset>,
+    ) -> chrono::DateTime<chrono::FixedOffset> {
+        match self {
+            Self::Fixed(offset) => date.with_timezone(offset),
+            Self::Local => {
+                let local = date.with_timezone(&chrono::Local);
+                local.with_timezone(&local.offset().fix())
+            }
+            Self::Named(tz) => {
+                let named = date.with_timezone(tz);
+                named.with_timezone(&named.offset().fix())
+            }
+            Self::Original => *date,
+            Self::Utc => date.with_timezone(&chrono::Utc.fix()),
+        }
+    }
+
+    /// Interprets the given wall clock time in this time zone.
+    ///
+    /// Wall clock times which are skipped or repeated due to daylight saving
+    /// time resolve to the earliest valid point in time. Since there is no
+    /// single original offset, the given one is used in this case.
+    fn resolve_local(
+        &self,
+        date: &chrono::NaiveDateTime,
+        original: &chrono::FixedOffset,
+    ) -> chrono::DateTime<chrono::FixedOffset> {
+        match self {
+            Self::Fixed(offset) => offset.from_local_datetime(date).unwrap(),
+            Self::Local => earliest(&chrono::Local, date),
+            Self::Named(tz) => earliest(tz, date),
+            Self::Original => original.from_local_datetime(date).unwrap(),
+            Self::Utc => chrono::Utc.fix().from_local_datetime(date).unwrap(),
+        }
+    }
+
+    /// Splits the given time span at the midnights of this time zone.
+    ///
+    /// Every part of the span is attributed to the calendar day it falls into.
+    /// A span whose end precedes its start is attributed as a whole to the
+    /// day of its end. For the original offsets, the offset of the end is used
+    /// for the whole span.
+    pub fn split_days(
+        &self,
+        start: &chrono::DateTime<chrono::FixedOffset>,
+        end: &chrono::DateTime<chrono::FixedOffset>,
+    ) -> Vec<(chrono::NaiveDate, chrono::Duration)> {
+        self.split(start, end, |time| time.date().succ().and_hms(0, 0, 0))
+            .into_iter()
+            .map(|(time, duration)| (time.date(), duration))
+            .collect()
+    }
+
+    /// Splits the given time span at the full hours of this time zone.
+    ///
+    /// Every part of the span is attributed to the wall clock time it starts
+    /// at, see `Timezone::split_days` for details.
+    pub fn split_hours(
+        &self,
+        start: &chrono::DateTime<chrono::FixedOffset>,
+        end: &chrono::DateTime<chrono::FixedOffset>,
+    ) -> Vec<(chrono::NaiveDateTime, chrono::Duration)> {
+        self.split(start, end, |time| {
+            time.date().and_hms(time.hour(), 0, 0) + chrono::Duration::hours(1)
+        })
+    }
+
+    /// Splits the given time span at the wall clock times returned by `next`
+    /// for the beginning of every part.
+    fn split<F>(
+        &self,
+        start: &chrono::DateTime<chrono::FixedOffset>,
+        end: &chrono::DateTime<chrono::FixedOffset>,
+        next: F,
+    ) -> Vec<(chrono::NaiveDateTime, chrono::Duration)>
+    where
+        F: Fn(&chrono::NaiveDateTime) -> chrono::NaiveDateTime,
+    {
+        let end = self.convert(end);
+        let mut start = match self {
+            Self::Original => start.with_timezone(end.offset()),
+            _ => self.convert(start),
+        };
+        let mut parts = vec![];
+
+        if end < start {
+            return vec![(end.naive_local(), end - start)];
+        }
+
+        while next(&start.naive_local()) <= end.naive_local() {
+            let boundary = self.resolve_local(&next(&start.naive_local()), end.offset());
+
+            parts.push((start.naive_local(), boundary - start));
+            start = boundary;
+        }
+        parts.push((start.naive_local(), end - start));
+
+        parts
+    }
+}
+
+/// Resolves a wall clock time to the earliest valid point in time.
+///
+/// Wall clock times skipped due to daylight saving time are moved forward
+/// until they exist.
+fn earliest<Tz: TimeZone>(
+    tz: &Tz,
+    date: &chrono::NaiveDateTime,
+) -> chrono::DateTime<chrono::FixedOffset> {
+    let mut date = *date;
+
+    loop {
+        if let Some(resolved) = tz.from_local_datetime(&date).earliest() {
+            break resolved.with_timezone(&resolved.offset().fix());
+        }
+        date += chrono::Duration::minutes(30);
+    }
+}
+
+impl std::fmt::Display for Timezone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Fixed(offset) => write!(f, "{}", offset),
+            Self::Local => write!(f, "local"),
+            Self::Named(tz) => write!(f, "{}", tz.name()),
+            Self::Original => write!(f, "original"),
+            Self::Utc => write!(f, "utc"),
+        }
+    }
+}
+
+impl std::str::FromStr for Timezone {
+    type Err = TimezoneParseError;
+
+    fn from_str(timezone: &str) -> Result<Self, Self::Err> {
+        match timezone.to_lowercase().as_str() {
+            "local" => Ok(Self::Local),
+            "original" => Ok(Self::Original),
+            "utc" | "z" => Ok(Self::Utc),
+            _ => {
+                let (sign, offset) = if let Some(offset) = timezone.strip_prefix('+') {
+                    (1, offset)
+                } else if let Some(offset) = timezone.strip_prefix('-') {
+                    (-1, offset)
+                } else {
+                    return timezone
+                        .parse()
+                        .map(Self::Named)
+                        .map_err(|_| TimezoneParseError::Unknown);
+                };
+                if offset.is_empty() || !offset.chars().all(|c| c.is_ascii_digit() || c == ':') {
+                    return Err(TimezoneParseError::Unknown);
+                }
+                let (hours, minutes) = match offset.split_once(':') {
+                    Some(parts) => parts,
+                    None if offset.len() == 4 => offset.split_at(2),
+                    None => (offset, "0"),
+                };
+                if hours.len() > 2 || minutes.len() > 2 {
+                    return Err(TimezoneParseError::OffsetOutOfRange);
+                }
+                let hours: i32 = hours.parse().map_err(|_| TimezoneParseError::Unknown)?;
+                let minutes: i32 = minutes.parse().map_err(|_| TimezoneParseError::Unknown)?;
+
+                chrono::FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+                    .filter(|_| minutes < 60)
+                    .map(Self::Fixed)
+                    .ok_or(TimezoneParseError::OffsetOutOfRange)
+            }
+        }
+    }
+}
+
+/// The set of errors which may occur.
+#[derive(Debug)]
+pub enum TimezoneParseError {
+    /// The offset exceeds a day or has more than 59 minutes.
+    OffsetOutOfRange,
+
+    /// The time zone is neither `local`, `original`, `utc`, a valid offset nor
+    /// known to the IANA time zone database.
+    Unknown,
+}
+
+impl std::fmt::Display for TimezoneParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OffsetOutOfRange => write!(f, "the offset is out of range"),
+            Self::Unknown => write!(
+                f,
+                "expected `local`, `original`, `utc`, an offset like `+02:00` or an IANA name like `Europe/Berlin`"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TimezoneParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::Timezone;
+
+    #[test]
+    fn parse_keywords_and_names() {
+        assert_eq!("UTC".parse::<Timezone>().unwrap(), Timezone::Utc);
+        assert_eq!("local".parse::<Timezone>().unwrap(), Timezone::Local);
+        assert_eq!("original".parse::<Timezone>().unwrap(), Timezone::Original);
+        assert_eq!(
+            "Europe/Berlin".parse::<Timezone>().unwrap(),
+            Timezone::Named(chrono_tz::Europe::Berlin)
+        );
+    }
+
+    #[test]
+    fn parse_offsets() {
+        let fixed = |seconds| Timezone::Fixed(chrono::FixedOffset::east(seconds));
+
+        assert_eq!("+02:00".parse::<Timezone>().unwrap(), fixed(7200));
+        assert_eq!("-0530".parse::<Timezone>().unwrap(), fixed(-19800));
+        assert_eq!("+3".parse::<Timezone>().unwrap(), fixed(10800));
+        assert!("+01:60".parse::<Timezone>().is_err());
+        assert!("+25:00".parse::<Timezone>().is_err());
+        assert!("+999999".parse::<Timezone>().is_err());
+    }
+
+    #[test]
+    fn parse_rejects_non_ascii_input() {
+        for input in ["ü", "+ü", "-üü:00", "+0ü00", "", "+"] {
+            assert!(input.parse::<Timezone>().is_err(), "{}", input);
+        }
+    }
+}
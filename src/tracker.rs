@@ -0,0 +1,382 @@
+//! The export of work sessions into the import formats of time trackers.
+//!
+//! The work of every author within a session becomes a time entry, such that
+//! the estimate can be billed without retyping it. The project, the
+//! description and the tags of an entry are taken from the repository's name,
+//! the authors or the commit subjects, as configured.
+
+use crate::{session::Session, timezone::Timezone, Commit};
+
+/// The supported time trackers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum TrackerFormat {
+    /// The CSV import of Toggl Track.
+    Toggl,
+
+    /// The CSV import of Clockify.
+    Clockify,
+
+    /// The CSV import of Harvest.
+    Harvest,
+
+    /// The JSON import of Timewarrior, with the project as the first tag.
+    Timewarrior,
+}
+
+/// The information a property of a time entry can be taken from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Field {
+    /// The name of the repository.
+    Repository,
+
+    /// The names of the session's authors.
+    Authors,
+
+    /// The subjects of the session's commits.
+    Subjects,
+}
+
+/// The assignment of fields to the properties of a time entry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mapping {
+    /// The field the description is taken from.
+    pub description: Field,
+
+    /// The field the project is taken from.
+    pub project: Field,
+
+    /// The fields the tags are taken from.
+    pub tags: Vec<Field>,
+}
+
+/// The consecutive commits of an author within a session.
+struct Span<'a> {
+    /// The commits, in chronological order.
+    commits: Vec<&'a Commit>,
+
+    /// The date of the last commit.
+    end: chrono::DateTime<chrono::FixedOffset>,
+
+    /// The date of the commit preceding the first one.
+    start: chrono::DateTime<chrono::FixedOffset>,
+}
+
+/// A single time entry.
+struct Entry<'a> {
+    /// The description.
+    description: String,
+
+    /// The email address of the session's author.
+    email: &'a str,
+
+    /// The end of the session.
+    end: chrono::DateTime<chrono::FixedOffset>,
+
+    /// The name of the session's author.
+    name: &'a str,
+
+    /// The project.
+    project: String,
+
+    /// The start of the session.
+    start: chrono::DateTime<chrono::FixedOffset>,
+
+    /// The tags.
+    tags: Vec<String>,
+}
+
+impl TrackerFormat {
+    /// Renders the time entries of the given sessions in this format.
+    ///
+    /// Every entry belongs to a single author. Like for `--authors`, the time
+    /// since the previous commit of a session is attributed to the author of
+    /// the next one, and consecutive spans of the same author become one
+    /// entry. Hence, the entries add up to the estimated time. The dates are
+    /// converted into the given time zone, except for Timewarrior, which
+    /// expects UTC. The given delimiter separates the fields of CSV files.
+    pub fn export(
+        self,
+        sessions: &[Session],
+        mapping: &Mapping,
+        repository: &str,
+        timezone: Timezone,
+        delimiter: crate::csv::Delimiter,
+    ) -> std::io::Result<Vec<u8>> {
+        let mut spans: Vec<Span> = vec![];
+
+        for session in sessions {
+            let mut current: Vec<Span> = vec![];
+
+            for interval in session.intervals() {
+                let commit = interval.commit();
+
+                match current.last_mut() {
+                    Some(span)
+                        if span.commits[0].author().email().to_lowercase()
+                            == commit.author().email().to_lowercase() =>
+                    {
+                        span.commits.push(commit);
+                        span.end = *interval.end();
+                    }
+                    _ => current.push(Span {
+                        commits: vec![commit],
+                        end: *interval.end(),
+                        start: *interval.start(),
+                    }),
+                }
+            }
+            spans.append(&mut current);
+        }
+
+        let entries: Vec<Entry> = spans
+            .iter()
+            .filter(|span| span.end > span.start)
+            .map(|span| {
+                let author = span.commits[0].author();
+                let value = |field: Field| match field {
+                    Field::Repository => repository.to_owned(),
+                    Field::Authors => author.name().to_owned(),
+                    Field::Subjects => span
+                        .commits
+                        .iter()
+                        .map(|commit| commit.subject())
+                        .collect::<Vec<_>>()
+                        .join("; "),
+                };
+
+                let tags = mapping.tags.iter().map(|field| value(*field));
+
+                Entry {
+                    description: value(mapping.description),
+                    email: author.email(),
+                    end: timezone.convert(&span.end),
+                    name: author.name(),
+                    project: value(mapping.project),
+                    start: timezone.convert(&span.start),
+                    tags: tags.collect(),
+                }
+            })
+            .collect();
+
+        match self {
+            Self::Toggl => CsvFormat::Toggl.write(&entries, delimiter),
+            Self::Clockify => CsvFormat::Clockify.write(&entries, delimiter),
+            Self::Harvest => CsvFormat::Harvest.write(&entries, delimiter),
+            Self::Timewarrior => timewarrior(&entries),
+        }
+    }
+}
+
+/// The time trackers importing CSV files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CsvFormat {
+    /// The CSV import of Toggl Track.
+    Toggl,
+
+    /// The CSV import of Clockify.
+    Clockify,
+
+    /// The CSV import of Harvest.
+    Harvest,
+}
+
+impl CsvFormat {
+    /// Renders the given entries as CSV with this format's columns.
+    fn write(
+        self,
+        entries: &[Entry],
+        delimiter: crate::csv::Delimiter,
+    ) -> std::io::Result<Vec<u8>> {
+        let mut writer = crate::csv::Writer::new(vec![], delimiter);
+        let duration = |entry: &Entry| {
+            let seconds = (entry.end - entry.start).num_seconds();
+
+            format!(
+                "{:02}:{:02}:{:02}",
+                seconds / 3600,
+                seconds / 60 % 60,
+                seconds % 60
+            )
+        };
+
+        match self {
+            Self::Toggl => {
+                writer.write_record([
+                    "User",
+                    "Email",
+                    "Project",
+                    "Description",
+                    "Start date",
+                    "Start time",
+                    "Duration",
+                    "Tags",
+                ])?;
+                for entry in entries {
+                    writer.write_record([
+                        entry.name.to_owned(),
+                        entry.email.to_owned(),
+                        entry.project.clone(),
+                        entry.description.clone(),
+                        entry.start.format("%Y-%m-%d").to_string(),
+                        entry.start.format("%H:%M:%S").to_string(),
+                        duration(entry),
+                        entry.tags.join(", "),
+                    ])?;
+                }
+            }
+            Self::Clockify => {
+                writer.write_record([
+                    "Project",
+                    "Description",
+                    "User",
+                    "Email",
+                    "Tags",
+                    "Start Date",
+                    "Start Time",
+                    "End Date",
+                    "End Time",
+                    "Duration (h)",
+                ])?;
+                for entry in entries {
+                    writer.write_record([
+                        entry.project.clone(),
+                        entry.description.clone(),
+                        entry.name.to_owned(),
+                        entry.email.to_owned(),
+                        entry.tags.join(", "),
+                        entry.start.format("%Y-%m-%d").to_string(),
+                        entry.start.format("%H:%M:%S").to_string(),
+                        entry.end.format("%Y-%m-%d").to_string(),
+                        entry.end.format("%H:%M:%S").to_string(),
+                        duration(entry),
+                    ])?;
+                }
+            }
+            Self::Harvest => {
+                writer.write_record([
+                    "Date",
+                    "Client",
+                    "Project",
+                    "Task",
+                    "Notes",
+                    "Hours",
+                    "First name",
+                    "Last name",
+                ])?;
+                for entry in entries {
+                    let (first_name, last_name) =
+                        entry.name.split_once(' ').unwrap_or((entry.name, ""));
+                    let hours = crate::session::hours(entry.end - entry.start);
+
+                    writer.write_record([
+                        entry.start.format("%Y-%m-%d").to_string(),
+                        String::new(),
+                        entry.project.clone(),
+                        String::new(),
+                        entry.description.clone(),
+                        format!("{:.2}", hours),
+                        first_name.to_owned(),
+                        last_name.to_owned(),
+                    ])?;
+                }
+            }
+        }
+
+        Ok(writer.into_inner())
+    }
+}
+
+/// Renders the given entries as the JSON import of Timewarrior.
+fn timewarrior(entries: &[Entry]) -> std::io::Result<Vec<u8>> {
+    let intervals: Vec<serde_json::Value> = entries
+        .iter()
+        .map(|entry| {
+            let utc = |date: &chrono::DateTime<chrono::FixedOffset>| {
+                date.with_timezone(&chrono::Utc)
+                    .format("%Y%m%dT%H%M%SZ")
+                    .to_string()
+            };
+            let mut tags = vec![entry.project.clone()];
+            for tag in &entry.tags {
+                if !tags.contains(tag) {
+                    tags.push(tag.clone());
+                }
+            }
+
+            serde_json::json!({
+                "start": utc(&entry.start),
+                "end": utc(&entry.end),
+                "tags": tags,
+                "annotation": entry.description,
+            })
+        })
+        .collect();
+
+    let mut json = serde_json::to_vec_pretty(&intervals)?;
+    json.push(b'\n');
+    Ok(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Field, Mapping, TrackerFormat};
+    use crate::{session::Session, timezone::Timezone};
+
+    /// Alternating commits by two authors within one session.
+    const LOG: &str = "commit 4444444444444444444444444444444444444444
+Author: Bob <bob@example.com>
+Date:   Wed Jun 1 11:00:00 2022 +0000
+
+    Bob's second commit
+
+commit 3333333333333333333333333333333333333333
+Author: Alice <alice@example.com>
+Date:   Wed Jun 1 10:30:00 2022 +0000
+
+    Alice's second commit
+
+commit 2222222222222222222222222222222222222222
+Author: Bob <bob@example.com>
+Date:   Wed Jun 1 10:00:00 2022 +0000
+
+    Bob's first commit
+
+commit 1111111111111111111111111111111111111111
+Author: Alice <alice@example.com>
+Date:   Wed Jun 1 09:00:00 2022 +0000
+
+    Alice's first commit
+";
+
+    #[test]
+    fn export_splits_the_entries_per_author() {
+        let (commits, _) = crate::Commit::parse_log(LOG);
+        let sessions = Session::detect(commits.iter().rev(), 3);
+        let mapping = Mapping {
+            description: Field::Authors,
+            project: Field::Repository,
+            tags: vec![],
+        };
+        let csv = TrackerFormat::Toggl
+            .export(
+                &sessions,
+                &mapping,
+                "repo",
+                Timezone::Utc,
+                ",".parse().unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "User,Email,Project,Description,\
+             Start date,Start time,Duration,Tags\r\n\
+             Bob,bob@example.com,repo,Bob,\
+             2022-06-01,09:00:00,01:00:00,\r\n\
+             Alice,alice@example.com,repo,Alice,\
+             2022-06-01,10:00:00,00:30:00,\r\n\
+             Bob,bob@example.com,repo,Bob,\
+             2022-06-01,10:30:00,00:30:00,\r\n"
+        );
+    }
+}